@@ -0,0 +1,119 @@
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+fn main() {
+    generate_day_modules();
+    record_build_environment();
+}
+
+// every `src/days/dayN.rs` is compiled in when its `dayN` feature is on, so a new day can't be
+// forgotten about in `days/mod.rs`.
+// days from other events live in `src/days/yearYYYY/dayN.rs`, and end up in a `yearYYYY` module.
+fn generate_day_modules() {
+    println!("cargo::rerun-if-changed=src/days");
+
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let days_dir = manifest_dir.join("src/days");
+
+    let mut modules = String::new();
+    write_day_modules(&mut modules, &days_dir);
+
+    let mut years = vec![];
+    for entry in std::fs::read_dir(&days_dir).unwrap() {
+        let path = entry.unwrap().path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some(Ok(year)) = name.strip_prefix("year").map(str::parse::<u32>) else {
+            continue;
+        };
+        if path.is_dir() {
+            years.push((year, path));
+        }
+    }
+    years.sort_unstable();
+
+    for (year, path) in years {
+        println!("cargo::rerun-if-changed={}", path.display());
+        writeln!(modules, "pub mod year{year} {{").unwrap();
+        write_day_modules(&mut modules, &path);
+        writeln!(modules, "}}").unwrap();
+    }
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("days.rs"), modules).unwrap();
+}
+
+fn write_day_modules(modules: &mut String, dir: &Path) {
+    let mut days = vec![];
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let Some(Ok(day)) = stem.strip_prefix("day").map(str::parse::<u32>) else {
+            continue;
+        };
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            days.push((day, path));
+        }
+    }
+    days.sort_unstable();
+
+    for (day, path) in days {
+        writeln!(modules, "#[cfg(feature = \"day{day}\")]").unwrap();
+        writeln!(modules, "#[path = {:?}]", path.display().to_string()).unwrap();
+        writeln!(modules, "pub mod day{day};").unwrap();
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_string())
+}
+
+// benchmark results get tagged with these, see `environment.rs`.
+fn record_build_environment() {
+    // the dirty state should follow any edit to the sources, not just to the day list.
+    println!("cargo::rerun-if-changed=src");
+    println!("cargo::rerun-if-changed=Cargo.toml");
+    println!("cargo::rerun-if-changed=.git/HEAD");
+    println!("cargo::rerun-if-changed=.git/index");
+    // committing or pulling moves the branch's ref rather than HEAD itself. Loose refs get packed
+    // into `packed-refs`, so watch both, but only if they exist, since a missing file would make
+    // cargo rerun this on every build.
+    let head_ref = command_output("git", &["symbolic-ref", "-q", "HEAD"]);
+    for path in head_ref.iter().map(String::as_str).chain(["packed-refs"]) {
+        let path = Path::new(".git").join(path);
+        if path.exists() {
+            println!("cargo::rerun-if-changed={}", path.display());
+        }
+    }
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".into());
+    let rustc_version = command_output(&rustc, &["--version"]).unwrap_or_else(|| "unknown".into());
+    let commit = command_output("git", &["rev-parse", "--short", "HEAD"]);
+    let dirty =
+        command_output("git", &["status", "--porcelain"]).is_some_and(|out| !out.is_empty());
+
+    println!("cargo::rustc-env=AOC_RUSTC_VERSION={rustc_version}");
+    println!(
+        "cargo::rustc-env=AOC_BUILD_PROFILE={}",
+        std::env::var("PROFILE").unwrap()
+    );
+    println!(
+        "cargo::rustc-env=AOC_TARGET_FEATURES={}",
+        std::env::var("CARGO_CFG_TARGET_FEATURE").unwrap_or_default()
+    );
+    println!(
+        "cargo::rustc-env=AOC_GIT_COMMIT={}",
+        commit.as_deref().unwrap_or("unknown")
+    );
+    println!("cargo::rustc-env=AOC_GIT_DIRTY={dirty}");
+}
@@ -0,0 +1,111 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Attribute, ItemFn, LitInt, LitStr};
+
+/// Registers a solver function as a runnable variant.
+///
+/// The variant is named after the function and belongs to the day of the enclosing `dayN` module,
+/// and the year of the enclosing `yearYYYY` module if there is one. All of these can be overridden
+/// with `#[variant(year = 2023, day = 7, name = "part2_fast")]`.
+#[proc_macro_attribute]
+pub fn variant(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut year: Option<LitInt> = None;
+    let mut day: Option<LitInt> = None;
+    let mut name: Option<LitStr> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("year") {
+            year = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("day") {
+            day = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("name") {
+            name = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `year`, `day` or `name`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+
+    let func = parse_macro_input!(item as ItemFn);
+    let ident = &func.sig.ident;
+    let name = name.unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+    let year = optional(year);
+    let day = optional(day);
+
+    let cfgs = cfg_attrs(&func);
+    let static_ident = format_ident!("__VARIANT_{}", ident.to_string().to_uppercase());
+
+    quote! {
+        #func
+
+        #(#cfgs)*
+        #[::linkme::distributed_slice(crate::VARIANTS)]
+        static #static_ident: crate::VariantEntry = crate::VariantEntry {
+            module: ::core::module_path!(),
+            year: #year,
+            day: #day,
+            name: #name,
+            runner: |ctx| crate::report_variant(ctx, #ident),
+        };
+    }
+    .into()
+}
+
+/// Registers a function that checks an input has the shape a day's variants expect. It's run
+/// before the variant when `--validate` is passed.
+///
+/// Like `#[variant]`, the year and day are taken from the enclosing modules unless they're given
+/// with `#[validator(year = 2023, day = 7)]`.
+#[proc_macro_attribute]
+pub fn validator(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut year: Option<LitInt> = None;
+    let mut day: Option<LitInt> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("year") {
+            year = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("day") {
+            day = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `year` or `day`"))
+        }
+    });
+    parse_macro_input!(args with parser);
+
+    let func = parse_macro_input!(item as ItemFn);
+    let ident = &func.sig.ident;
+    let year = optional(year);
+    let day = optional(day);
+
+    let cfgs = cfg_attrs(&func);
+    let static_ident = format_ident!("__VALIDATOR_{}", ident.to_string().to_uppercase());
+
+    quote! {
+        #func
+
+        #(#cfgs)*
+        #[::linkme::distributed_slice(crate::VALIDATORS)]
+        static #static_ident: crate::ValidatorEntry = crate::ValidatorEntry {
+            module: ::core::module_path!(),
+            year: #year,
+            day: #day,
+            validate: #ident,
+        };
+    }
+    .into()
+}
+
+// the registration has to disappear along with the function if it's configured out.
+fn cfg_attrs(func: &ItemFn) -> impl Iterator<Item = &Attribute> {
+    func.attrs.iter().filter(|attr| attr.path().is_ident("cfg"))
+}
+
+fn optional(lit: Option<LitInt>) -> proc_macro2::TokenStream {
+    match lit {
+        Some(lit) => quote!(::core::option::Option::Some(#lit)),
+        None => quote!(::core::option::Option::None),
+    }
+}
@@ -0,0 +1,499 @@
+use crate::bitset::{Bits, Bitset};
+
+/// A 2D grid of bits, stored row by row in a [`Bitset`] with each row padded out to a whole
+/// number of words.
+///
+/// Since rows start on word boundaries, moving the whole grid by a cell is a handful of word
+/// shifts per row, which is what lets BFS and flood fills step a whole frontier at a time.
+/// The padding bits past `width` are always kept clear.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    // words per row.
+    stride: usize,
+    bits: Bitset,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let stride = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            stride,
+            bits: Bitset::new(stride * 64 * height),
+        }
+    }
+
+    /// Builds a grid with the cells that `is_set` picks out set.
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        mut is_set: impl FnMut(usize, usize) -> bool,
+    ) -> Self {
+        let mut grid = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                if is_set(x, y) {
+                    grid.set(x, y);
+                }
+            }
+        }
+        grid
+    }
+
+    /// Lays out a `width * y + x` style bitset, like the ones the grid days build, as a grid.
+    /// A last row that's only partly covered is padded out with unset cells.
+    pub fn from_bitset(bitset: &Bitset, width: usize) -> Self {
+        let height = bitset.len().div_ceil(width);
+        Self::from_fn(width, height, |x, y| {
+            let ix = width * y + x;
+            ix < bitset.len() && bitset.get(ix)
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The underlying bitset. Cell `(x, y)` is at `64 * stride * y + x`, not `width * y + x`.
+    pub fn bitset(&self) -> &Bitset {
+        &self.bits
+    }
+
+    fn index(&self, x: usize, y: usize) -> usize {
+        debug_assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of bounds"
+        );
+        self.stride * 64 * y + x
+    }
+
+    fn row(&self, y: usize) -> &[u64] {
+        &self.bits.bits()[self.stride * y..self.stride * (y + 1)]
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        let stride = self.stride;
+        &mut self.bits.bits_mut()[stride * y..stride * (y + 1)]
+    }
+
+    /// The bits of the last word in a row that are actual cells.
+    fn row_tail_mask(&self) -> u64 {
+        match self.width & 63 {
+            0 => u64::MAX,
+            n => u64::MAX << (64 - n),
+        }
+    }
+
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.bits.get(self.index(x, y))
+    }
+
+    #[inline]
+    pub fn set(&mut self, x: usize, y: usize) {
+        let ix = self.index(x, y);
+        self.bits.set(ix);
+    }
+
+    #[inline]
+    pub fn clear(&mut self, x: usize, y: usize) {
+        let ix = self.index(x, y);
+        self.bits.clear(ix);
+    }
+
+    pub fn clear_all(&mut self) {
+        self.bits.clear_all();
+    }
+
+    pub fn count_ones(&self) -> u32 {
+        self.bits.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.bits().iter().all(|&word| word == 0)
+    }
+
+    /// The `(x, y)` of every set cell, row by row.
+    pub fn iter_ones(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let row_len = self.stride * 64;
+        self.bits
+            .iter_ones()
+            .map(move |ix| (ix % row_len, ix / row_len))
+    }
+
+    pub fn copy_from(&mut self, src: &BitGrid) {
+        self.assert_same_shape(src);
+        self.bits.copy_from(&src.bits);
+    }
+
+    #[track_caller]
+    fn assert_same_shape(&self, other: &BitGrid) {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "grids have different shapes"
+        );
+    }
+
+    pub fn or_assign(&mut self, rhs: &BitGrid) {
+        self.assert_same_shape(rhs);
+        self.bits |= &rhs.bits;
+    }
+
+    pub fn and_assign(&mut self, rhs: &BitGrid) {
+        self.assert_same_shape(rhs);
+        self.bits &= &rhs.bits;
+    }
+
+    /// Clears every cell that's set in `rhs`.
+    pub fn and_not_assign(&mut self, rhs: &BitGrid) {
+        self.assert_same_shape(rhs);
+        self.bits.and_not_assign(&rhs.bits);
+    }
+
+    /// Moves every cell one column towards `x = 0`. The first column falls off, and the last one
+    /// comes in empty.
+    pub fn shift_left(&mut self) {
+        for y in 0..self.height {
+            let row = self.row_mut(y);
+            for i in 0..row.len() {
+                // msb-first, so lower x is the higher bit, and the carry comes from the next word.
+                let carry = row.get(i + 1).map_or(0, |next| next >> 63);
+                row[i] = (row[i] << 1) | carry;
+            }
+        }
+    }
+
+    /// Moves every cell one column away from `x = 0`. The last column falls off, and the first
+    /// one comes in empty.
+    pub fn shift_right(&mut self) {
+        let tail_mask = self.row_tail_mask();
+        for y in 0..self.height {
+            let row = self.row_mut(y);
+            for i in (0..row.len()).rev() {
+                let carry = if i == 0 { 0 } else { row[i - 1] << 63 };
+                row[i] = (row[i] >> 1) | carry;
+            }
+            // the last column was pushed into the padding.
+            if let Some(last) = row.last_mut() {
+                *last &= tail_mask;
+            }
+        }
+    }
+
+    /// Moves every cell one row towards `y = 0`.
+    pub fn shift_up(&mut self) {
+        let stride = self.stride;
+        let words = self.bits.bits_mut();
+        words.copy_within(stride.., 0);
+        let len = words.len();
+        words[len.saturating_sub(stride)..].fill(0);
+    }
+
+    /// Moves every cell one row away from `y = 0`.
+    pub fn shift_down(&mut self) {
+        let stride = self.stride;
+        let words = self.bits.bits_mut();
+        let len = words.len();
+        words.copy_within(..len.saturating_sub(stride), stride.min(len));
+        words[..stride.min(len)].fill(0);
+    }
+
+    /// Grows the set cells into their four orthogonal neighbours.
+    pub fn dilate(&mut self) {
+        let mut scratch = self.clone();
+        self.dilate_with(&mut scratch);
+    }
+
+    /// [`dilate`](Self::dilate), using `scratch` instead of allocating a grid of its own.
+    pub fn dilate_with(&mut self, scratch: &mut BitGrid) {
+        scratch.copy_from(self);
+        let tail_mask = self.row_tail_mask();
+        for y in 0..self.height {
+            let row = scratch.row(y);
+            for i in 0..self.stride {
+                let word = row[i];
+                let next = row.get(i + 1).map_or(0, |next| next >> 63);
+                let prev = if i == 0 { 0 } else { row[i - 1] << 63 };
+                let mut out = word | (word << 1) | next | (word >> 1) | prev;
+                if y > 0 {
+                    out |= scratch.row(y - 1)[i];
+                }
+                if y + 1 < self.height {
+                    out |= scratch.row(y + 1)[i];
+                }
+                if i + 1 == self.stride {
+                    out &= tail_mask;
+                }
+                self.row_mut(y)[i] = out;
+            }
+        }
+    }
+
+    /// Every place `pattern` fits in `self` with at most `tolerance` cells different, as the
+    /// position of its top left corner, in row order. Both the set and the unset cells of the
+    /// pattern have to match.
+    ///
+    /// Each placement lines a window of `self`'s row words up with the pattern's with a pair of
+    /// shifts, and counts the differences a word at a time, giving up on the placement as soon as
+    /// there are too many.
+    pub fn find_pattern<'a>(
+        &'a self,
+        pattern: &'a BitGrid,
+        tolerance: u32,
+    ) -> impl Iterator<Item = PatternMatch> + 'a {
+        let xs = (self.width + 1).saturating_sub(pattern.width);
+        let ys = (self.height + 1).saturating_sub(pattern.height);
+        (0..ys)
+            .flat_map(move |y| (0..xs).map(move |x| (x, y)))
+            .filter_map(move |(x, y)| {
+                let mismatches = self.mismatches_at(pattern, x, y, tolerance)?;
+                Some(PatternMatch { x, y, mismatches })
+            })
+    }
+
+    /// The number of cells that differ between `pattern` and the part of `self` it covers at
+    /// `(x, y)`, or `None` if that's more than `tolerance`.
+    fn mismatches_at(&self, pattern: &BitGrid, x: usize, y: usize, tolerance: u32) -> Option<u32> {
+        let (word_offset, shift) = (x >> 6, x & 63);
+        let tail_mask = pattern.row_tail_mask();
+        let mut mismatches = 0;
+        for py in 0..pattern.height {
+            let row = self.row(y + py);
+            for (i, &expected) in pattern.row(py).iter().enumerate() {
+                let ix = word_offset + i;
+                // the window of the row starting at `x`, msb-first like everything else.
+                let mut window = row[ix] << shift;
+                if shift != 0 {
+                    window |= row.get(ix + 1).map_or(0, |next| next >> (64 - shift));
+                }
+                if i + 1 == pattern.stride {
+                    window &= tail_mask;
+                }
+                mismatches += (window ^ expected).count_ones();
+                if mismatches > tolerance {
+                    return None;
+                }
+            }
+        }
+        Some(mismatches)
+    }
+
+    /// Visits the cells reachable from `(x, y)` through set cells of `self`, a layer at a time.
+    /// `visit` gets the distance and the cells at that distance, starting from `(0, {(x, y)})`,
+    /// and stops the search early by returning `false`.
+    ///
+    /// Every step handles the whole frontier with a few bitwise ops per word, rather than a queue
+    /// pop per cell.
+    pub fn bfs_layers(&self, x: usize, y: usize, mut visit: impl FnMut(u32, &BitGrid) -> bool) {
+        if !self.get(x, y) {
+            return;
+        }
+        let mut seen = BitGrid::new(self.width, self.height);
+        let mut frontier = BitGrid::new(self.width, self.height);
+        let mut scratch = BitGrid::new(self.width, self.height);
+        frontier.set(x, y);
+
+        let mut depth = 0;
+        while !frontier.is_empty() {
+            if !visit(depth, &frontier) {
+                return;
+            }
+            seen.or_assign(&frontier);
+            frontier.dilate_with(&mut scratch);
+            frontier.and_assign(self);
+            frontier.and_not_assign(&seen);
+            depth += 1;
+        }
+    }
+
+    /// The distance from `(x, y)` to `(to_x, to_y)` through set cells of `self`.
+    pub fn bfs_distance(
+        &self,
+        (x, y): (usize, usize),
+        (to_x, to_y): (usize, usize),
+    ) -> Option<u32> {
+        let mut distance = None;
+        self.bfs_layers(x, y, |depth, frontier| {
+            if frontier.get(to_x, to_y) {
+                distance = Some(depth);
+            }
+            distance.is_none()
+        });
+        distance
+    }
+
+    /// Every cell reachable from `(x, y)` through set cells of `self`, including `(x, y)` itself
+    /// if it's set.
+    pub fn flood_fill(&self, x: usize, y: usize) -> BitGrid {
+        let mut filled = BitGrid::new(self.width, self.height);
+        self.bfs_layers(x, y, |_, frontier| {
+            filled.or_assign(frontier);
+            true
+        });
+        filled
+    }
+}
+
+/// Where [`BitGrid::find_pattern`] found its pattern.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PatternMatch {
+    pub x: usize,
+    pub y: usize,
+    /// How many cells were different from the pattern.
+    pub mismatches: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::VecDeque;
+
+    type Shift = fn(&mut BitGrid);
+
+    fn grid_strategy() -> impl Strategy<Value = (usize, usize, Vec<bool>)> {
+        (1usize..150, 1usize..12).prop_flat_map(|(width, height)| {
+            (
+                Just(width),
+                Just(height),
+                prop::collection::vec(prop::bool::weighted(0.7), width * height),
+            )
+        })
+    }
+
+    fn from_model(width: usize, height: usize, model: &[bool]) -> BitGrid {
+        BitGrid::from_fn(width, height, |x, y| model[width * y + x])
+    }
+
+    fn to_model(grid: &BitGrid) -> Vec<bool> {
+        let (width, height) = (grid.width(), grid.height());
+        (0..width * height)
+            .map(|i| grid.get(i % width, i / width))
+            .collect()
+    }
+
+    /// The same thing as `to_model`, but read from the words, so it'd catch junk in the padding.
+    fn padding_is_clear(grid: &BitGrid) -> bool {
+        grid.count_ones() as usize == to_model(grid).iter().filter(|&&bit| bit).count()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_shifts((width, height, model) in grid_strategy()) {
+            let grid = from_model(width, height, &model);
+            let at = |x: isize, y: isize| {
+                (0..width as isize).contains(&x)
+                    && (0..height as isize).contains(&y)
+                    && model[width * y as usize + x as usize]
+            };
+            let cases: [(Shift, isize, isize); 4] = [
+                (BitGrid::shift_left, 1, 0),
+                (BitGrid::shift_right, -1, 0),
+                (BitGrid::shift_up, 0, 1),
+                (BitGrid::shift_down, 0, -1),
+            ];
+            for (shift, dx, dy) in cases {
+                let mut shifted = grid.clone();
+                shift(&mut shifted);
+                let expected = (0..width * height)
+                    .map(|i| at((i % width) as isize + dx, (i / width) as isize + dy))
+                    .collect::<Vec<_>>();
+                prop_assert_eq!(to_model(&shifted), expected);
+                prop_assert!(padding_is_clear(&shifted));
+            }
+
+            let mut dilated = grid.clone();
+            dilated.dilate();
+            let expected = (0..width * height)
+                .map(|i| {
+                    let (x, y) = ((i % width) as isize, (i / width) as isize);
+                    [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)]
+                        .iter()
+                        .any(|&(dx, dy)| at(x + dx, y + dy))
+                })
+                .collect::<Vec<_>>();
+            prop_assert_eq!(to_model(&dilated), expected);
+            prop_assert!(padding_is_clear(&dilated));
+        }
+
+        #[test]
+        fn prop_bfs((width, height, model) in grid_strategy(), start: (usize, usize)) {
+            let grid = from_model(width, height, &model);
+            let (sx, sy) = (start.0 % width, start.1 % height);
+
+            let mut distances = vec![None; width * height];
+            let mut queue = VecDeque::from([(sx, sy, 0u32)]);
+            while let Some((x, y, depth)) = queue.pop_front() {
+                if !model[width * y + x] || distances[width * y + x].is_some() {
+                    continue;
+                }
+                distances[width * y + x] = Some(depth);
+                if x + 1 < width {
+                    queue.push_back((x + 1, y, depth + 1));
+                }
+                if x > 0 {
+                    queue.push_back((x - 1, y, depth + 1));
+                }
+                if y + 1 < height {
+                    queue.push_back((x, y + 1, depth + 1));
+                }
+                if y > 0 {
+                    queue.push_back((x, y - 1, depth + 1));
+                }
+            }
+
+            let mut layered = vec![None; width * height];
+            grid.bfs_layers(sx, sy, |depth, frontier| {
+                for (x, y) in frontier.iter_ones() {
+                    assert!(layered[width * y + x].is_none());
+                    layered[width * y + x] = Some(depth);
+                }
+                true
+            });
+            prop_assert_eq!(&layered, &distances);
+
+            let reachable = distances.iter().map(Option::is_some).collect::<Vec<_>>();
+            prop_assert_eq!(to_model(&grid.flood_fill(sx, sy)), reachable);
+            let (tx, ty) = (width - 1, height - 1);
+            prop_assert_eq!(grid.bfs_distance((sx, sy), (tx, ty)), distances[width * ty + tx]);
+        }
+
+        #[test]
+        fn prop_find_pattern(
+            (width, height, model) in grid_strategy(),
+            (pw, ph) in (1usize..80, 1usize..5),
+            origin: (usize, usize),
+            tolerance in 0u32..4,
+        ) {
+            let grid = from_model(width, height, &model);
+            // take the pattern from somewhere in the grid, so there's always at least one match.
+            let (pw, ph) = (pw.min(width), ph.min(height));
+            let (ox, oy) = (origin.0 % (width - pw + 1), origin.1 % (height - ph + 1));
+            let pattern = BitGrid::from_fn(pw, ph, |x, y| grid.get(ox + x, oy + y));
+
+            let mut expected = vec![];
+            for y in 0..=height - ph {
+                for x in 0..=width - pw {
+                    let mismatches = (0..ph)
+                        .flat_map(|py| (0..pw).map(move |px| (px, py)))
+                        .filter(|&(px, py)| grid.get(x + px, y + py) != pattern.get(px, py))
+                        .count() as u32;
+                    if mismatches <= tolerance {
+                        expected.push(PatternMatch { x, y, mismatches });
+                    }
+                }
+            }
+            let found = grid.find_pattern(&pattern, tolerance).collect::<Vec<_>>();
+            let origin = PatternMatch { x: ox, y: oy, mismatches: 0 };
+            prop_assert!(found.contains(&origin));
+            prop_assert_eq!(found, expected);
+        }
+    }
+}
@@ -1,5 +1,12 @@
 use std::fmt::Write;
+use std::io;
+use std::iter::FusedIterator;
+use std::ops::{Bound, RangeBounds};
 
+/// A fixed-length set of bits, stored most significant bit first within each word.
+///
+/// Bits in the last word past `len` can end up set (through `bits_mut`, or negation), so anything
+/// that looks at whole words needs to mask them off.
 #[derive(Clone, Debug)]
 pub struct Bitset {
     len: usize,
@@ -13,29 +20,306 @@ impl Bitset {
             bits: vec![0u64; (len >> SHIFT) + 1],
         }
     }
-
-    pub fn clear_all(&mut self) {
-        self.bits.fill(0);
-    }
 }
 
 const HI64: u64 = 1u64 << 63;
 const SHIFT: u32 = 6;
 
-impl Bitset {
-    pub fn len(&self) -> usize {
+/// The number of words that hold bits below `len`. Bitsets of the same length can have
+/// different amounts of storage, so this is what pairs of bitsets have to be walked by.
+const fn word_count(len: usize) -> usize {
+    (len >> SHIFT) + ((len & 63 != 0) as usize)
+}
+
+/// The bits of the last word that are actually part of a set of length `len`.
+fn tail_mask(len: usize) -> u64 {
+    match len & 63 {
+        0 => u64::MAX,
+        n => u64::MAX << (64 - n),
+    }
+}
+
+/// The words of a set of length `len`, with the bits past `len` cleared.
+fn masked_words(len: usize, words: &[u64]) -> impl Iterator<Item = u64> + '_ {
+    let last = word_count(len).wrapping_sub(1);
+    let tail_mask = tail_mask(len);
+    words
+        .iter()
+        .enumerate()
+        .map(move |(i, &word)| if i == last { word & tail_mask } else { word })
+}
+
+/// Turns `range` into a `start..end` pair, panicking if it doesn't fit in a set of length `len`.
+#[track_caller]
+fn resolve_range(len: usize, range: impl RangeBounds<usize>) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range start {start} is past its end {end}");
+    assert!(end <= len, "range end {end} is past the length {len}");
+    (start, end)
+}
+
+/// The words covering `range`, each paired with a mask of the bits in it that fall inside the
+/// range. Only the first and last words can be partial.
+#[track_caller]
+fn range_masks(len: usize, range: impl RangeBounds<usize>) -> impl Iterator<Item = (usize, u64)> {
+    let (start, end) = resolve_range(len, range);
+    let first = start >> SHIFT;
+    let last = end.wrapping_sub(1) >> SHIFT;
+    let words = if start < end { first..last + 1 } else { 0..0 };
+    words.map(move |word_ix| {
+        let mut mask = u64::MAX;
+        if word_ix == first {
+            mask &= u64::MAX >> (start & 63);
+        }
+        if word_ix == last {
+            mask &= u64::MAX << (63 - ((end - 1) & 63));
+        }
+        (word_ix, mask)
+    })
+}
+
+/// The operations shared by [`Bitset`] and [`FixedBitset`], so that code walking a set doesn't
+/// have to care which one it has.
+///
+/// Everything here is written in terms of `bits()`, the words holding the bits below `len`.
+pub trait Bits {
+    fn len(&self) -> usize;
+    fn bits(&self) -> &[u64];
+    fn bits_mut(&mut self) -> &mut [u64];
+
+    /// Whether there are no bits at all, set or not. See [`any`](Self::any) for whether any are
+    /// set.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    #[track_caller]
+    fn get(&self, ix: usize) -> bool {
+        debug_assert!(ix < self.len());
+        self.bits()[ix >> SHIFT] & HI64.wrapping_shr(ix as u32) != 0
+    }
+
+    #[inline]
+    #[track_caller]
+    fn set(&mut self, ix: usize) {
+        debug_assert!(ix < self.len());
+        self.bits_mut()[ix >> SHIFT] |= HI64.wrapping_shr(ix as u32);
+    }
+
+    #[inline]
+    #[track_caller]
+    fn clear(&mut self, ix: usize) {
+        debug_assert!(ix < self.len());
+        self.bits_mut()[ix >> SHIFT] &= !HI64.wrapping_shr(ix as u32);
+    }
+
+    fn clear_all(&mut self) {
+        self.bits_mut().fill(0);
+    }
+
+    fn count_ones(&self) -> u32 {
+        let [head @ .., tail] = self.bits() else {
+            return 0;
+        };
+        // make sure the out of bounds bits don't contribute to the total.
+        words::count_ones(head) + (tail & tail_mask(self.len())).count_ones()
+    }
+
+    fn count_zeros(&self) -> u32 {
+        self.len() as u32 - self.count_ones()
+    }
+
+    /// Whether any bit is set.
+    fn any(&self) -> bool {
+        let [head @ .., tail] = self.bits() else {
+            return false;
+        };
+        words::any(head) || tail & tail_mask(self.len()) != 0
+    }
+
+    /// Whether every bit is set. Always true for an empty set.
+    fn all(&self) -> bool {
+        let [head @ .., tail] = self.bits() else {
+            return true;
+        };
+        let tail_mask = tail_mask(self.len());
+        words::all(head) && tail & tail_mask == tail_mask
+    }
+
+    fn copy_from(&mut self, src: &Self) {
+        assert_eq!(self.len(), src.len());
+        self.bits_mut().copy_from_slice(src.bits());
+    }
+
+    /// Flips every bit in the set.
+    fn not_assign(&mut self) {
+        self.bits_mut().iter_mut().for_each(|word| *word = !*word);
+    }
+
+    fn and_assign(&mut self, rhs: &Self) {
+        assert_eq!(self.len(), rhs.len());
+        words::and_assign(self.bits_mut(), rhs.bits());
+    }
+
+    fn or_assign(&mut self, rhs: &Self) {
+        assert_eq!(self.len(), rhs.len());
+        words::or_assign(self.bits_mut(), rhs.bits());
+    }
+
+    fn xor_assign(&mut self, rhs: &Self) {
+        assert_eq!(self.len(), rhs.len());
+        words::xor_assign(self.bits_mut(), rhs.bits());
+    }
+
+    /// Clears every bit that's set in `rhs`.
+    fn and_not_assign(&mut self, rhs: &Self) {
+        assert_eq!(self.len(), rhs.len());
+        words::and_not_assign(self.bits_mut(), rhs.bits());
+    }
+
+    /// Whether every bit set in `self` is also set in `other`.
+    fn is_subset(&self, other: &Self) -> bool {
+        assert_eq!(self.len(), other.len());
+        masked_words(self.len(), self.bits())
+            .zip(other.bits())
+            .all(|(lhs, &rhs)| lhs & !rhs == 0)
+    }
+
+    fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Whether no bit is set in both `self` and `other`.
+    fn is_disjoint(&self, other: &Self) -> bool {
+        assert_eq!(self.len(), other.len());
+        masked_words(self.len(), self.bits())
+            .zip(other.bits())
+            .all(|(lhs, &rhs)| lhs & rhs == 0)
+    }
+
+    fn set_range(&mut self, range: impl RangeBounds<usize>) {
+        let len = self.len();
+        let words = self.bits_mut();
+        for (word_ix, mask) in range_masks(len, range) {
+            words[word_ix] |= mask;
+        }
+    }
+
+    fn clear_range(&mut self, range: impl RangeBounds<usize>) {
+        let len = self.len();
+        let words = self.bits_mut();
+        for (word_ix, mask) in range_masks(len, range) {
+            words[word_ix] &= !mask;
+        }
+    }
+
+    fn toggle_range(&mut self, range: impl RangeBounds<usize>) {
+        let len = self.len();
+        let words = self.bits_mut();
+        for (word_ix, mask) in range_masks(len, range) {
+            words[word_ix] ^= mask;
+        }
+    }
+
+    fn count_ones_in(&self, range: impl RangeBounds<usize>) -> u32 {
+        range_masks(self.len(), range)
+            .map(|(word_ix, mask)| (self.bits()[word_ix] & mask).count_ones())
+            .sum()
+    }
+
+    /// Whether any bit in `range` is set. Always false for an empty range.
+    fn any_in(&self, range: impl RangeBounds<usize>) -> bool {
+        range_masks(self.len(), range).any(|(word_ix, mask)| self.bits()[word_ix] & mask != 0)
+    }
+
+    /// Whether every bit in `range` is set. Always true for an empty range.
+    fn all_in(&self, range: impl RangeBounds<usize>) -> bool {
+        range_masks(self.len(), range).all(|(word_ix, mask)| self.bits()[word_ix] & mask == mask)
+    }
+
+    /// The indices of the set bits, in increasing order.
+    fn iter_ones(&self) -> BitIndices<'_> {
+        BitIndices::new(self.bits(), 0, 0, self.len())
+    }
+
+    /// The indices of the unset bits, in increasing order.
+    fn iter_zeros(&self) -> BitIndices<'_> {
+        BitIndices::new(self.bits(), u64::MAX, 0, self.len())
+    }
+
+    fn iter_ones_in(&self, range: impl RangeBounds<usize>) -> BitIndices<'_> {
+        let (start, end) = resolve_range(self.len(), range);
+        BitIndices::new(self.bits(), 0, start, end)
+    }
+
+    fn iter_zeros_in(&self, range: impl RangeBounds<usize>) -> BitIndices<'_> {
+        let (start, end) = resolve_range(self.len(), range);
+        BitIndices::new(self.bits(), u64::MAX, start, end)
+    }
+
+    fn first_one(&self) -> Option<usize> {
+        self.iter_ones().next()
+    }
+
+    /// The first set bit strictly after `ix`.
+    fn next_one_after(&self, ix: usize) -> Option<usize> {
+        self.iter_ones_in(ix.saturating_add(1).min(self.len())..)
+            .next()
+    }
+}
+
+impl Bits for Bitset {
+    fn len(&self) -> usize {
         self.len
     }
-    pub fn capacity(&self) -> usize {
-        u64::BITS as usize * self.bits.len()
+    fn bits(&self) -> &[u64] {
+        &self.bits[..word_count(self.len)]
+    }
+    fn bits_mut(&mut self) -> &mut [u64] {
+        &mut self.bits[..word_count(self.len)]
+    }
+
+    // these go straight to the storage, since slicing it down to `bits()` first costs a bit on
+    // every access in the hot loops.
+    #[inline]
+    #[track_caller]
+    fn get(&self, ix: usize) -> bool {
+        debug_assert!(ix < self.len);
+        self.bits[ix >> SHIFT] & HI64.wrapping_shr(ix as u32) != 0
     }
-    pub fn bits(&self) -> &[u64] {
-        let ix = (self.len >> SHIFT) + ((self.len & 63 != 0) as usize);
-        &self.bits[..ix]
+
+    #[inline]
+    #[track_caller]
+    fn set(&mut self, ix: usize) {
+        debug_assert!(ix < self.len);
+        self.bits[ix >> SHIFT] |= HI64.wrapping_shr(ix as u32);
     }
-    pub fn bits_mut(&mut self) -> &mut [u64] {
-        let ix = (self.len >> SHIFT) + ((self.len & 63 != 0) as usize);
-        &mut self.bits[..ix]
+
+    #[inline]
+    #[track_caller]
+    fn clear(&mut self, ix: usize) {
+        debug_assert!(ix < self.len);
+        self.bits[ix >> SHIFT] &= !HI64.wrapping_shr(ix as u32);
+    }
+
+    fn clear_all(&mut self) {
+        self.bits.fill(0);
+    }
+}
+
+impl Bitset {
+    pub fn capacity(&self) -> usize {
+        u64::BITS as usize * self.bits.len()
     }
 
     pub fn push(&mut self, bit: bool) {
@@ -53,25 +337,12 @@ impl Bitset {
         self.get(self.len)
     }
 
-    #[inline]
-    pub fn get(&self, ix: usize) -> bool {
-        debug_assert!(ix < self.len);
-        self.bits[ix >> SHIFT] & HI64.wrapping_shr(ix as u32) != 0
-    }
-
     #[inline]
     pub unsafe fn get_unchecked(&self, ix: usize) -> bool {
         debug_assert!(ix < self.len);
         *self.bits.get_unchecked(ix >> SHIFT) & HI64.wrapping_shr(ix as u32) != 0
     }
 
-    #[inline]
-    #[track_caller]
-    pub fn set(&mut self, ix: usize) {
-        debug_assert!(ix < self.len);
-        self.bits[ix >> SHIFT] |= HI64.wrapping_shr(ix as u32);
-    }
-
     #[inline]
     #[track_caller]
     pub unsafe fn set_unchecked(&mut self, ix: usize) {
@@ -90,38 +361,408 @@ impl Bitset {
         }
     }
 
-    #[inline]
-    pub fn clear(&mut self, ix: usize) {
-        debug_assert!(ix < self.len);
-        self.bits[ix >> SHIFT] &= !HI64.wrapping_shr(ix as u32);
-    }
-
     #[inline]
     pub unsafe fn clear_unchecked(&mut self, ix: usize) {
         debug_assert!(ix < self.len);
         *self.bits.get_unchecked_mut(ix >> SHIFT) &= !HI64.wrapping_shr(ix as u32);
     }
 
-    pub fn count_ones(&self) -> u32 {
-        let [head @ .., tail] = self.bits() else {
-            return 0;
+    pub fn not(out: &mut Bitset, src: &Bitset) {
+        out.copy_from(src);
+        out.not_assign();
+    }
+
+    pub fn and_not(out: &mut Bitset, lhs: &Bitset, rhs: &Bitset) {
+        assert_eq!(out.len, lhs.len);
+        assert_eq!(out.len, rhs.len);
+        words::and_not(out.bits_mut(), lhs.bits(), rhs.bits());
+    }
+}
+
+impl Bitset {
+    /// The bits as hex, four to a digit and msb-first like the words themselves, so a set that's a
+    /// whole number of words reads the same as its words written out one after another.
+    pub fn to_hex(&self) -> String {
+        let words = masked_words(self.len, self.bits()).collect::<Vec<_>>();
+        (0..self.len.div_ceil(4))
+            .map(|i| {
+                let nibble = (words[i >> 4] >> (60 - 4 * (i & 15))) & 0xf;
+                char::from_digit(nibble as u32, 16).unwrap()
+            })
+            .collect()
+    }
+
+    /// Reads `to_hex`'s output back into a set of `len` bits. Whitespace and underscores are
+    /// skipped, so a long fixture can be split up over several lines.
+    pub fn from_hex(len: usize, hex: &str) -> eyre::Result<Bitset> {
+        let mut bitset = Bitset::new(len);
+        let digits = hex.chars().filter(|&ch| !ch.is_whitespace() && ch != '_');
+        let mut count = 0;
+        for (i, ch) in digits.enumerate() {
+            let Some(nibble) = ch.to_digit(16) else {
+                eyre::bail!("'{ch}' isn't a hex digit");
+            };
+            if i < len.div_ceil(4) {
+                bitset.bits_mut()[i >> 4] |= (nibble as u64) << (60 - 4 * (i & 15));
+            }
+            count += 1;
+        }
+        if count != len.div_ceil(4) {
+            eyre::bail!(
+                "expected {} hex digits for {len} bits, got {count}",
+                len.div_ceil(4)
+            );
+        }
+        bitset.check_tail()?;
+        Ok(bitset)
+    }
+
+    /// The length as 8 little-endian bytes, followed by the bits packed msb-first into bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = (self.len as u64).to_le_bytes().to_vec();
+        let payload = masked_words(self.len, self.bits()).flat_map(u64::to_be_bytes);
+        bytes.extend(payload.take(self.len.div_ceil(8)));
+        bytes
+    }
+
+    /// Reads `to_bytes`'s output back into a set.
+    pub fn from_bytes(bytes: &[u8]) -> eyre::Result<Bitset> {
+        let Some((len, payload)) = bytes.split_first_chunk::<8>() else {
+            eyre::bail!(
+                "{} bytes is too short to hold a bitset's length",
+                bytes.len()
+            );
         };
-        let sum: u32 = head.iter().copied().map(u64::count_ones).sum();
-        // make sure the out of bounds bits don't contribute to the total.
-        // fails when self.len == 0, but we don't get here if that's the case.
-        let mask = u64::MAX << 63 - (self.len - 1 & 63);
-        sum + (tail & mask).count_ones()
+        let len = usize::try_from(u64::from_le_bytes(*len))?;
+        if payload.len() != len.div_ceil(8) {
+            let expected = len.div_ceil(8);
+            eyre::bail!(
+                "expected {expected} bytes for {len} bits, got {}",
+                payload.len()
+            );
+        }
+        let mut bitset = Bitset::new(len);
+        for (word, chunk) in bitset.bits_mut().iter_mut().zip(payload.chunks(8)) {
+            let mut be_bytes = [0; 8];
+            be_bytes[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_be_bytes(be_bytes);
+        }
+        bitset.check_tail()?;
+        Ok(bitset)
     }
 
-    pub fn count_zeros(&self) -> u32 {
-        let [head @ .., tail] = self.bits() else {
-            return 0;
+    /// Fails if any of the bits in the last word past `len` are set, since a serialised set that
+    /// has them probably wasn't `len` long to begin with.
+    fn check_tail(&self) -> eyre::Result<()> {
+        if let Some(&tail) = self.bits().last() {
+            if tail & !tail_mask(self.len) != 0 {
+                eyre::bail!(
+                    "there are bits set past the end of the {} bit set",
+                    self.len
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// The set as a `width` wide grid of 1 bit pixels, with each cell a `scale` by `scale` square
+    /// and every row padded out to a whole byte. `set` is the pixel value for set cells.
+    fn packed_pixels(&self, width: usize, scale: usize, set: bool) -> (usize, usize, Vec<u8>) {
+        assert!(width > 0 && scale > 0, "width={width}, scale={scale}");
+        let height = self.len.div_ceil(width);
+        let row_bytes = (width * scale).div_ceil(8);
+        let mut out = Vec::with_capacity(row_bytes * height * scale);
+        for y in 0..height {
+            let start = out.len();
+            out.resize(start + row_bytes, 0);
+            for x in 0..width * scale {
+                let ix = width * y + x / scale;
+                let cell = ix < self.len && self.get(ix);
+                out[start + (x >> 3)] |= ((cell == set) as u8) << (7 - (x & 7));
+            }
+            for _ in 1..scale {
+                out.extend_from_within(start..start + row_bytes);
+            }
+        }
+        (width * scale, height * scale, out)
+    }
+
+    /// Draws the set as a `width` wide grid into a binary PBM image, with set cells in black.
+    /// Anything past the end of the last row is left white.
+    pub fn write_pbm(&self, width: usize, scale: usize, mut out: impl io::Write) -> io::Result<()> {
+        let (width, height, pixels) = self.packed_pixels(width, scale, true);
+        write!(out, "P4\n{width} {height}\n")?;
+        out.write_all(&pixels)?;
+        out.flush()
+    }
+
+    /// Draws the set as a `width` wide grid into a black and white PNG, like
+    /// [`write_pbm`](Self::write_pbm).
+    #[cfg(feature = "visualize")]
+    pub fn write_png(&self, width: usize, scale: usize, out: impl io::Write) -> eyre::Result<()> {
+        // in grayscale, 1 is white.
+        let (width, height, pixels) = self.packed_pixels(width, scale, false);
+        let mut encoder = png::Encoder::new(out, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::One);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&pixels)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+// bits past `len` aren't part of the set, so they shouldn't make two sets different.
+impl PartialEq for Bitset {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && words_eq(self.len, self.bits(), other.bits())
+    }
+}
+
+impl Eq for Bitset {}
+
+impl FromIterator<bool> for Bitset {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let mut bitset = Bitset::new(0);
+        iter.into_iter().for_each(|bit| bitset.push(bit));
+        bitset
+    }
+}
+
+/// The number of words a [`FixedBitset`] of `bits` bits needs. Stable Rust can't work out an
+/// array length from a const parameter, so this has to be spelled out next to it.
+pub const fn words_for(bits: usize) -> usize {
+    word_count(bits)
+}
+
+/// A [`Bitset`] whose length is known at compile time, stored inline.
+///
+/// `WORDS` has to be `words_for(BITS)`, eg. `FixedBitset<{ 71 * 71 }, { words_for(71 * 71) }>`,
+/// which is checked when the set is created. With both known up front every loop over the words
+/// has a fixed trip count, and there's no allocation.
+#[derive(Copy, Clone, Debug)]
+pub struct FixedBitset<const BITS: usize, const WORDS: usize> {
+    bits: [u64; WORDS],
+}
+
+impl<const BITS: usize, const WORDS: usize> FixedBitset<BITS, WORDS> {
+    const CHECK_WORDS: () = assert!(WORDS == words_for(BITS), "WORDS must be words_for(BITS)");
+
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CHECK_WORDS;
+        Self { bits: [0; WORDS] }
+    }
+}
+
+impl<const BITS: usize, const WORDS: usize> Default for FixedBitset<BITS, WORDS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const BITS: usize, const WORDS: usize> Bits for FixedBitset<BITS, WORDS> {
+    #[inline]
+    fn len(&self) -> usize {
+        BITS
+    }
+    #[inline]
+    fn bits(&self) -> &[u64] {
+        &self.bits
+    }
+    #[inline]
+    fn bits_mut(&mut self) -> &mut [u64] {
+        &mut self.bits
+    }
+}
+
+impl<const BITS: usize, const WORDS: usize> PartialEq for FixedBitset<BITS, WORDS> {
+    fn eq(&self, other: &Self) -> bool {
+        words_eq(BITS, &self.bits, &other.bits)
+    }
+}
+
+impl<const BITS: usize, const WORDS: usize> Eq for FixedBitset<BITS, WORDS> {}
+
+/// An iterator over the indices of the set (or unset) bits in a range of a [`Bitset`].
+///
+/// Each step skips straight to the next interesting bit with `leading_zeros`/`trailing_zeros`,
+/// so a sparse set is walked in time proportional to its set bits, plus one step per word.
+#[derive(Clone, Debug)]
+pub struct BitIndices<'a> {
+    bits: &'a [u64],
+    // xored into every word, so that looking for zeros is looking for ones in the inverted word.
+    flip: u64,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> BitIndices<'a> {
+    fn new(bits: &'a [u64], flip: u64, start: usize, end: usize) -> Self {
+        Self {
+            bits,
+            flip,
+            start,
+            end,
+        }
+    }
+}
+
+impl Iterator for BitIndices<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        while self.start < self.end {
+            let word_ix = self.start >> SHIFT;
+            // msb-first, so the bits at or after `start` are the low ones.
+            let word = (self.bits[word_ix] ^ self.flip) & (u64::MAX >> (self.start & 63));
+            if word == 0 {
+                self.start = (word_ix + 1) << SHIFT;
+                continue;
+            }
+            let ix = (word_ix << SHIFT) + word.leading_zeros() as usize;
+            if ix >= self.end {
+                break;
+            }
+            self.start = ix + 1;
+            return Some(ix);
+        }
+        self.start = self.end;
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.start))
+    }
+}
+
+impl DoubleEndedIterator for BitIndices<'_> {
+    fn next_back(&mut self) -> Option<usize> {
+        while self.start < self.end {
+            let last = self.end - 1;
+            let word_ix = last >> SHIFT;
+            let word = (self.bits[word_ix] ^ self.flip) & (u64::MAX << (63 - (last & 63)));
+            if word == 0 {
+                self.end = word_ix << SHIFT;
+                continue;
+            }
+            let ix = (word_ix << SHIFT) + 63 - word.trailing_zeros() as usize;
+            if ix < self.start {
+                break;
+            }
+            self.end = ix;
+            return Some(ix);
+        }
+        self.end = self.start;
+        None
+    }
+}
+
+impl FusedIterator for BitIndices<'_> {}
+
+/// Words per rank block. Ranks are stored once per block, and anything finer is counted on the
+/// fly, so a lookup costs at most this many popcounts.
+const RANK_BLOCK_WORDS: usize = 8;
+/// One out of this many ones has the block it's in recorded, to narrow down the search in `select`.
+const SELECT_SAMPLE: usize = 512;
+
+/// A succinct rank/select index over a [`Bitset`], for mapping the set bits onto `0..count_ones`.
+///
+/// This takes about one extra `u32` per 512 bits. The bitset can't change while the index is
+/// around, since the index would go stale.
+#[derive(Clone, Debug)]
+pub struct RankSelect<'a> {
+    bitset: &'a Bitset,
+    // the number of ones before each block, plus the total at the end.
+    block_ranks: Vec<u32>,
+    // the block holding every `SELECT_SAMPLE`th one.
+    select_samples: Vec<u32>,
+}
+
+impl<'a> RankSelect<'a> {
+    pub fn new(bitset: &'a Bitset) -> Self {
+        let words = masked_words(bitset.len, bitset.bits()).collect::<Vec<_>>();
+        let mut block_ranks = Vec::with_capacity(words.len() / RANK_BLOCK_WORDS + 2);
+        let mut select_samples = vec![];
+        let mut total = 0u32;
+        for (block_ix, block) in words.chunks(RANK_BLOCK_WORDS).enumerate() {
+            block_ranks.push(total);
+            let ones: u32 = block.iter().copied().map(u64::count_ones).sum();
+            while select_samples.len() * SELECT_SAMPLE < (total + ones) as usize {
+                select_samples.push(block_ix as u32);
+            }
+            total += ones;
+        }
+        block_ranks.push(total);
+        Self {
+            bitset,
+            block_ranks,
+            select_samples,
+        }
+    }
+
+    pub fn bitset(&self) -> &'a Bitset {
+        self.bitset
+    }
+
+    pub fn count_ones(&self) -> usize {
+        *self.block_ranks.last().unwrap() as usize
+    }
+
+    /// The number of set bits before `ix`. `ix` can be anything up to and including the length, so
+    /// `rank(len)` is the total.
+    pub fn rank(&self, ix: usize) -> usize {
+        assert!(ix <= self.bitset.len, "ix={ix}, len={}", self.bitset.len);
+        let word_ix = ix >> SHIFT;
+        let block_ix = word_ix / RANK_BLOCK_WORDS;
+        let bits = self.bitset.bits();
+        let whole: u32 = bits[block_ix * RANK_BLOCK_WORDS..word_ix]
+            .iter()
+            .copied()
+            .map(u64::count_ones)
+            .sum();
+        // the bits before `ix` in its own word are the high ones.
+        let partial = match ix & 63 {
+            0 => 0,
+            n => (bits[word_ix] & (u64::MAX << (64 - n))).count_ones(),
         };
-        let sum: u32 = head.iter().copied().map(u64::count_zeros).sum();
-        // make sure the out of bounds bits don't contribute to the total.
-        let mask = u64::MAX << 63 - (self.len - 1 & 63);
-        // let tail = tail | u64::MAX.wrapping_shr(self.len as u32);
-        sum + (tail | !mask).count_zeros()
+        (self.block_ranks[block_ix] + whole + partial) as usize
+    }
+
+    /// The index of the `k`th set bit, counting from zero, so that `rank(select(k)) == k`.
+    pub fn select(&self, k: usize) -> Option<usize> {
+        if k >= self.count_ones() {
+            return None;
+        }
+        // the block is somewhere between the ones holding the samples on either side of `k`.
+        let sample_ix = k / SELECT_SAMPLE;
+        let lo = self.select_samples[sample_ix] as usize;
+        let hi = self
+            .select_samples
+            .get(sample_ix + 1)
+            .map_or(self.block_ranks.len() - 1, |&block_ix| {
+                block_ix as usize + 1
+            });
+        let block_ix =
+            lo + self.block_ranks[lo + 1..hi].partition_point(|&rank| rank as usize <= k);
+
+        let mut remaining = k - self.block_ranks[block_ix] as usize;
+        let last = word_count(self.bitset.len) - 1;
+        for word_ix in block_ix * RANK_BLOCK_WORDS.. {
+            let mut word = self.bitset.bits[word_ix];
+            if word_ix == last {
+                word &= tail_mask(self.bitset.len);
+            }
+            let ones = word.count_ones() as usize;
+            if remaining < ones {
+                for _ in 0..remaining {
+                    word &= !(HI64 >> word.leading_zeros());
+                }
+                return Some((word_ix << SHIFT) + word.leading_zeros() as usize);
+            }
+            remaining -= ones;
+        }
+        unreachable!()
     }
 }
 
@@ -171,21 +812,463 @@ mod tests {
             assert_eq!(bitset.count_zeros(), i as u32);
         }
     }
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_write_pbm() {
+        let bitset = ["#........#", "##########"]
+            .iter()
+            .flat_map(|row| row.bytes().map(|ch| ch == b'#'))
+            .collect::<Bitset>();
+        let mut pbm = vec![];
+        bitset.write_pbm(10, 2, &mut pbm).unwrap();
+        // 20 pixels is 3 bytes a row, padded out with zeros, and every row is drawn twice.
+        let mut expected = b"P4\n20 4\n".to_vec();
+        expected.extend([0xc0, 0x00, 0x30].repeat(2));
+        expected.extend([0xff, 0xff, 0xf0].repeat(2));
+        assert_eq!(pbm, expected);
+    }
+
+    #[test]
+    fn test_bad_serialised() {
+        assert!(Bitset::from_hex(8, "f").is_err());
+        assert!(Bitset::from_hex(8, "fff").is_err());
+        assert!(Bitset::from_hex(8, "fg").is_err());
+        // 6 bits, but the last digit has the 7th and 8th set.
+        assert!(Bitset::from_hex(6, "ff").is_err());
+        assert!(Bitset::from_bytes(&[1, 2, 3]).is_err());
+        assert!(Bitset::from_bytes(&[9, 0, 0, 0, 0, 0, 0, 0, 0xff]).is_err());
+        assert!(Bitset::from_bytes(&[4, 0, 0, 0, 0, 0, 0, 0, 0xff]).is_err());
+    }
+
+    #[cfg(feature = "visualize")]
+    #[test]
+    fn test_write_png() {
+        let bitset = [true, false, false, true, true]
+            .into_iter()
+            .collect::<Bitset>();
+        let mut png = vec![];
+        bitset.write_png(2, 3, &mut png).unwrap();
+        let decoder = png::Decoder::new(png.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+        assert_eq!((info.width, info.height), (6, 9));
+        // set cells are black, and the cell past the end of the last row is white.
+        assert_eq!(pixels[0], 0b0001_1100);
+        assert_eq!(pixels[info.line_size * 3], 0b1110_0000);
+        assert_eq!(pixels[info.line_size * 6], 0b0001_1100);
+    }
+
+    /// Builds a bitset out of `model`, with junk in the bits past its length, which nothing should
+    /// ever be able to see.
+    fn from_model(model: &[bool], junk: u64) -> Bitset {
+        let mut bitset = model.iter().copied().collect::<Bitset>();
+        if model.len() & 63 != 0 {
+            let last = word_count(model.len()) - 1;
+            bitset.bits[last] |= junk & !tail_mask(model.len());
+        }
+        bitset
+    }
+
+    fn to_model(bitset: &Bitset) -> Vec<bool> {
+        (0..bitset.len()).map(|i| bitset.get(i)).collect()
+    }
+
+    /// Two models of the same length, which is often not a multiple of 64.
+    fn model_pair() -> impl Strategy<Value = (Vec<bool>, Vec<bool>)> {
+        (0usize..300).prop_flat_map(|len| {
+            (
+                prop::collection::vec(any::<bool>(), len),
+                prop::collection::vec(any::<bool>(), len),
+            )
+        })
+    }
+
+    type ThreeOperand = fn(&mut Bitset, &Bitset, &Bitset);
+    type ModelOp = fn(bool, bool) -> bool;
+
+    fn zip_model(lhs: &[bool], rhs: &[bool], op: impl Fn(bool, bool) -> bool) -> Vec<bool> {
+        lhs.iter().zip(rhs).map(|(&l, &r)| op(l, r)).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_binary_ops((lhs, rhs) in model_pair(), junk: [u64; 3]) {
+            let a = from_model(&lhs, junk[0]);
+            let b = from_model(&rhs, junk[1]);
+            let mut out = from_model(&vec![false; lhs.len()], junk[2]);
+
+            let cases: [(ThreeOperand, ModelOp); 4] = [
+                (Bitset::bitand, |l, r| l & r),
+                (Bitset::bitor, |l, r| l | r),
+                (Bitset::bitxor, |l, r| l ^ r),
+                (Bitset::and_not, |l, r| l & !r),
+            ];
+            for (op, model_op) in cases {
+                op(&mut out, &a, &b);
+                prop_assert_eq!(to_model(&out), zip_model(&lhs, &rhs, model_op));
+            }
+
+            prop_assert_eq!(to_model(&(&a & &b)), zip_model(&lhs, &rhs, |l, r| l & r));
+            prop_assert_eq!(to_model(&(&a | &b)), zip_model(&lhs, &rhs, |l, r| l | r));
+            prop_assert_eq!(to_model(&(&a ^ &b)), zip_model(&lhs, &rhs, |l, r| l ^ r));
+            prop_assert_eq!(to_model(&!&a), zip_model(&lhs, &lhs, |l, _| !l));
+
+            let mut assigned = a.clone();
+            assigned &= &b;
+            prop_assert_eq!(to_model(&assigned), zip_model(&lhs, &rhs, |l, r| l & r));
+            let mut assigned = a.clone();
+            assigned |= &b;
+            prop_assert_eq!(to_model(&assigned), zip_model(&lhs, &rhs, |l, r| l | r));
+            let mut assigned = a.clone();
+            assigned ^= &b;
+            prop_assert_eq!(to_model(&assigned), zip_model(&lhs, &rhs, |l, r| l ^ r));
+            let mut assigned = a.clone();
+            assigned.and_not_assign(&b);
+            prop_assert_eq!(to_model(&assigned), zip_model(&lhs, &rhs, |l, r| l & !r));
+
+            Bitset::not(&mut out, &a);
+            prop_assert_eq!(to_model(&out), zip_model(&lhs, &lhs, |l, _| !l));
+            prop_assert_eq!(out.count_ones() as usize, lhs.iter().filter(|&&l| !l).count());
+        }
+
+        #[test]
+        fn prop_relations((lhs, rhs) in model_pair(), junk: [u64; 2]) {
+            let a = from_model(&lhs, junk[0]);
+            let b = from_model(&rhs, junk[1]);
+
+            let subset = lhs.iter().zip(&rhs).all(|(&l, &r)| !l || r);
+            let superset = lhs.iter().zip(&rhs).all(|(&l, &r)| l || !r);
+            let disjoint = lhs.iter().zip(&rhs).all(|(&l, &r)| !(l && r));
+            prop_assert_eq!(a.is_subset(&b), subset);
+            prop_assert_eq!(a.is_superset(&b), superset);
+            prop_assert_eq!(a.is_disjoint(&b), disjoint);
+
+            prop_assert!((&a & &b).is_subset(&a));
+            prop_assert!((&a | &b).is_superset(&b));
+            prop_assert!(a.is_disjoint(&!&a));
+
+            prop_assert_eq!(a.any(), lhs.iter().any(|&l| l));
+            prop_assert_eq!(a.all(), lhs.iter().all(|&l| l));
+            let (ones, zeros) = (from_model(&vec![true; lhs.len()], junk[0]), from_model(&vec![false; lhs.len()], junk[0]));
+            prop_assert!(ones.all() && (ones.any() || lhs.is_empty()));
+            prop_assert!(!zeros.any() && (!zeros.all() || lhs.is_empty()));
+
+            prop_assert_eq!(a == b, lhs == rhs);
+            // the junk differs, but it's past the end so the sets are still equal.
+            prop_assert_eq!(&a, &from_model(&lhs, !junk[0]));
+        }
+
+        #[test]
+        fn prop_copy((lhs, rhs) in model_pair(), junk: u64) {
+            let src = from_model(&lhs, junk);
+            let mut dst = from_model(&rhs, !junk);
+            dst.copy_from(&src);
+            prop_assert_eq!(to_model(&dst), lhs.clone());
+
+            // storage sized differently from `src`, since it didn't grow one bit at a time.
+            let mut dst = Bitset::new(lhs.len());
+            copy(&mut dst, &src);
+            prop_assert_eq!(&dst, &src);
+        }
+
+        #[test]
+        fn prop_serialise(model in prop::collection::vec(any::<bool>(), 0..300), junk: u64) {
+            let bitset = from_model(&model, junk);
+            let hex = bitset.to_hex();
+            prop_assert_eq!(hex.len(), model.len().div_ceil(4));
+            prop_assert_eq!(&Bitset::from_hex(model.len(), &hex).unwrap(), &bitset);
+            let bytes = bitset.to_bytes();
+            prop_assert_eq!(bytes.len(), 8 + model.len().div_ceil(8));
+            prop_assert_eq!(&Bitset::from_bytes(&bytes).unwrap(), &bitset);
+        }
+
+        #[test]
+        fn prop_iter(model in prop::collection::vec(any::<bool>(), 0..300), junk: u64, cut: (usize, usize)) {
+            let bitset = from_model(&model, junk);
+            let ones = (0..model.len()).filter(|&i| model[i]).collect::<Vec<_>>();
+            let zeros = (0..model.len()).filter(|&i| !model[i]).collect::<Vec<_>>();
+
+            prop_assert_eq!(bitset.iter_ones().collect::<Vec<_>>(), ones.clone());
+            prop_assert_eq!(bitset.iter_zeros().collect::<Vec<_>>(), zeros.clone());
+            let rev_ones = ones.iter().rev().copied().collect::<Vec<_>>();
+            prop_assert_eq!(bitset.iter_ones().rev().collect::<Vec<_>>(), rev_ones);
+            let rev_zeros = zeros.iter().rev().copied().collect::<Vec<_>>();
+            prop_assert_eq!(bitset.iter_zeros().rev().collect::<Vec<_>>(), rev_zeros);
+
+            let start = cut.0 % (model.len() + 1);
+            let end = start + cut.1 % (model.len() - start + 1);
+            let in_range = |i: &&usize| (start..end).contains(*i);
+            let range_ones = ones.iter().filter(in_range).copied().collect::<Vec<_>>();
+            let range_zeros = zeros.iter().filter(in_range).copied().collect::<Vec<_>>();
+            prop_assert_eq!(bitset.iter_ones_in(start..end).collect::<Vec<_>>(), range_ones.clone());
+            prop_assert_eq!(bitset.iter_zeros_in(start..end).collect::<Vec<_>>(), range_zeros);
+
+            // alternating ends should still meet in the middle without repeating anything.
+            let mut iter = bitset.iter_ones_in(start..end);
+            let (mut front, mut back) = (vec![], vec![]);
+            loop {
+                match (iter.next(), iter.next_back()) {
+                    (Some(f), Some(b)) => { front.push(f); back.push(b); }
+                    (Some(f), None) => { front.push(f); break; }
+                    _ => break,
+                }
+            }
+            front.extend(back.into_iter().rev());
+            prop_assert_eq!(front, range_ones);
+
+            prop_assert_eq!(bitset.first_one(), ones.first().copied());
+            for i in 0..model.len() {
+                let next = ones.iter().copied().find(|&one| one > i);
+                prop_assert_eq!(bitset.next_one_after(i), next);
+            }
+        }
+
+        #[test]
+        fn prop_ranges(model in prop::collection::vec(any::<bool>(), 0..300), junk: u64, cut: (usize, usize)) {
+            let start = cut.0 % (model.len() + 1);
+            let end = start + cut.1 % (model.len() - start + 1);
+            let bitset = from_model(&model, junk);
+
+            let span = &model[start..end];
+            let ones = span.iter().filter(|&&bit| bit).count();
+            prop_assert_eq!(bitset.count_ones_in(start..end) as usize, ones);
+            prop_assert_eq!(bitset.any_in(start..end), span.iter().any(|&bit| bit));
+            prop_assert_eq!(bitset.all_in(start..end), span.iter().all(|&bit| bit));
+
+            let edit = |op: fn(&mut Bitset, std::ops::Range<usize>), model_op: fn(bool) -> bool| {
+                let mut edited = bitset.clone();
+                op(&mut edited, start..end);
+                let mut expected = model.clone();
+                expected[start..end].iter_mut().for_each(|bit| *bit = model_op(*bit));
+                (to_model(&edited), expected)
+            };
+            let (set, expected) = edit(|b, r| b.set_range(r), |_| true);
+            prop_assert_eq!(set, expected);
+            let (cleared, expected) = edit(|b, r| b.clear_range(r), |_| false);
+            prop_assert_eq!(cleared, expected);
+            let (toggled, expected) = edit(|b, r| b.toggle_range(r), |bit| !bit);
+            prop_assert_eq!(toggled, expected);
+
+            // the whole set, through the unbounded and inclusive forms.
+            prop_assert_eq!(bitset.count_ones_in(..), bitset.count_ones());
+            if !model.is_empty() {
+                prop_assert_eq!(bitset.count_ones_in(..=model.len() - 1), bitset.count_ones());
+            }
+        }
+
+        #[test]
+        fn prop_rank_select(model in prop::collection::vec(any::<bool>(), 0..3000), junk: u64, sparse: bool) {
+            // mostly empty sets, so that the ones are spread over lots of blocks.
+            let model = if sparse {
+                model.chunks(16).flat_map(|chunk| chunk.iter().map(|&bit| bit && chunk.iter().all(|&b| b))).collect()
+            } else {
+                model
+            };
+            let bitset = from_model(&model, junk);
+            let index = RankSelect::new(&bitset);
+            let ones = (0..model.len()).filter(|&i| model[i]).collect::<Vec<_>>();
+
+            prop_assert_eq!(index.count_ones(), ones.len());
+            for ix in 0..=model.len() {
+                prop_assert_eq!(index.rank(ix), ones.partition_point(|&one| one < ix));
+            }
+            for (k, &one) in ones.iter().enumerate() {
+                prop_assert_eq!(index.select(k), Some(one));
+            }
+            prop_assert_eq!(index.select(ones.len()), None);
+        }
+
+        #[test]
+        fn prop_fixed(lhs in prop::collection::vec(any::<bool>(), 130), rhs in prop::collection::vec(any::<bool>(), 130), cut: (usize, usize)) {
+            type Fixed = FixedBitset<130, { words_for(130) }>;
+            let fixed = |model: &[bool]| {
+                let mut bitset = Fixed::new();
+                model.iter().enumerate().filter(|(_, &bit)| bit).for_each(|(i, _)| bitset.set(i));
+                bitset
+            };
+            let (a, b) = (fixed(&lhs), fixed(&rhs));
+            let (dyn_a, dyn_b) = (from_model(&lhs, 0), from_model(&rhs, 0));
+
+            // anything generic over `Bits` should see the same set either way.
+            fn summary(bits: &impl Bits) -> (Vec<usize>, Vec<usize>, u32, u32) {
+                let ones = bits.iter_ones().collect();
+                let zeros = bits.iter_zeros().rev().collect();
+                (ones, zeros, bits.count_ones(), bits.count_zeros())
+            }
+            prop_assert_eq!(summary(&a), summary(&dyn_a));
+            prop_assert_eq!(summary(&(a & b)), summary(&(&dyn_a & &dyn_b)));
+            prop_assert_eq!(summary(&(a | b)), summary(&(&dyn_a | &dyn_b)));
+            prop_assert_eq!(summary(&(a ^ b)), summary(&(&dyn_a ^ &dyn_b)));
+            prop_assert_eq!(summary(&!a), summary(&!&dyn_a));
+            prop_assert_eq!(a.is_subset(&b), dyn_a.is_subset(&dyn_b));
+            prop_assert_eq!(a == b, lhs == rhs);
+            prop_assert_eq!(!!a, a);
+
+            let start = cut.0 % 131;
+            let end = start + cut.1 % (131 - start);
+            let (mut ranged, mut dyn_ranged) = (a, dyn_a.clone());
+            ranged.toggle_range(start..end);
+            dyn_ranged.toggle_range(start..end);
+            prop_assert_eq!(summary(&ranged), summary(&dyn_ranged));
+            prop_assert_eq!(a.count_ones_in(start..end), dyn_a.count_ones_in(start..end));
+        }
+    }
 }
 
 pub fn copy(dst: &mut Bitset, src: &Bitset) {
-    assert_eq!(dst.len, src.len);
-    unsafe { std::ptr::copy_nonoverlapping(&src.bits, &mut dst.bits, src.bits.len()) };
+    dst.copy_from(src);
+}
+
+/// The loops over whole runs of words behind the bulk set operations. With `nightly-simd` these
+/// go a vector of words at a time, and the scalar loop only picks up what's left at the end.
+mod words {
+    #[cfg(feature = "nightly-simd")]
+    use std::simd::{cmp::SimdPartialEq, num::SimdUint, u64x4};
+
+    #[cfg(feature = "nightly-simd")]
+    const LANES: usize = 4;
+
+    macro_rules! binary_kernel {
+        ($assign:ident, $three:ident, |$a:ident, $b:ident| $body:expr) => {
+            pub fn $assign(dst: &mut [u64], src: &[u64]) {
+                debug_assert_eq!(dst.len(), src.len());
+                #[cfg(feature = "nightly-simd")]
+                let (dst, src) = {
+                    let (dst_chunks, dst_rest) = dst.as_chunks_mut::<LANES>();
+                    let (src_chunks, src_rest) = src.as_chunks::<LANES>();
+                    for (dst, src) in dst_chunks.iter_mut().zip(src_chunks) {
+                        let ($a, $b) = (u64x4::from_array(*dst), u64x4::from_array(*src));
+                        *dst = ($body).to_array();
+                    }
+                    (dst_rest, src_rest)
+                };
+                for (dst, &src) in dst.iter_mut().zip(src) {
+                    let ($a, $b) = (*dst, src);
+                    *dst = $body;
+                }
+            }
+
+            pub fn $three(out: &mut [u64], lhs: &[u64], rhs: &[u64]) {
+                debug_assert_eq!(out.len(), lhs.len());
+                debug_assert_eq!(out.len(), rhs.len());
+                #[cfg(feature = "nightly-simd")]
+                let (out, lhs, rhs) = {
+                    let (out_chunks, out_rest) = out.as_chunks_mut::<LANES>();
+                    let (lhs_chunks, lhs_rest) = lhs.as_chunks::<LANES>();
+                    let (rhs_chunks, rhs_rest) = rhs.as_chunks::<LANES>();
+                    let chunks = out_chunks.iter_mut().zip(lhs_chunks).zip(rhs_chunks);
+                    for ((out, lhs), rhs) in chunks {
+                        let ($a, $b) = (u64x4::from_array(*lhs), u64x4::from_array(*rhs));
+                        *out = ($body).to_array();
+                    }
+                    (out_rest, lhs_rest, rhs_rest)
+                };
+                for ((out, &$a), &$b) in out.iter_mut().zip(lhs).zip(rhs) {
+                    *out = $body;
+                }
+            }
+        };
+    }
+
+    binary_kernel!(and_assign, and, |a, b| a & b);
+    binary_kernel!(or_assign, or, |a, b| a | b);
+    binary_kernel!(xor_assign, xor, |a, b| a ^ b);
+    binary_kernel!(and_not_assign, and_not, |a, b| a & !b);
+
+    pub fn count_ones(words: &[u64]) -> u32 {
+        #[cfg(feature = "nightly-simd")]
+        let (vector_sum, words) = {
+            let (chunks, rest) = words.as_chunks::<LANES>();
+            let sum = chunks
+                .iter()
+                .map(|&chunk| u64x4::from_array(chunk).count_ones())
+                .fold(u64x4::splat(0), |sum, ones| sum + ones);
+            (sum.reduce_sum() as u32, rest)
+        };
+        #[cfg(not(feature = "nightly-simd"))]
+        let vector_sum = 0;
+        vector_sum + words.iter().copied().map(u64::count_ones).sum::<u32>()
+    }
+
+    pub fn eq(lhs: &[u64], rhs: &[u64]) -> bool {
+        debug_assert_eq!(lhs.len(), rhs.len());
+        #[cfg(feature = "nightly-simd")]
+        let (lhs, rhs) = {
+            let (lhs_chunks, lhs_rest) = lhs.as_chunks::<LANES>();
+            let (rhs_chunks, rhs_rest) = rhs.as_chunks::<LANES>();
+            let mut chunks = lhs_chunks.iter().zip(rhs_chunks);
+            if chunks
+                .any(|(&lhs, &rhs)| u64x4::from_array(lhs).simd_ne(u64x4::from_array(rhs)).any())
+            {
+                return false;
+            }
+            (lhs_rest, rhs_rest)
+        };
+        lhs == rhs
+    }
+
+    /// Whether any word has any bit set.
+    pub fn any(words: &[u64]) -> bool {
+        #[cfg(feature = "nightly-simd")]
+        let words = {
+            let (chunks, rest) = words.as_chunks::<LANES>();
+            if chunks
+                .iter()
+                .any(|&chunk| u64x4::from_array(chunk).simd_ne(u64x4::splat(0)).any())
+            {
+                return true;
+            }
+            rest
+        };
+        words.iter().any(|&word| word != 0)
+    }
+
+    /// Whether every word has every bit set.
+    pub fn all(words: &[u64]) -> bool {
+        #[cfg(feature = "nightly-simd")]
+        let words = {
+            let (chunks, rest) = words.as_chunks::<LANES>();
+            let full = u64x4::splat(u64::MAX);
+            if !chunks
+                .iter()
+                .all(|&chunk| u64x4::from_array(chunk).simd_eq(full).all())
+            {
+                return false;
+            }
+            rest
+        };
+        words.iter().all(|&word| word == u64::MAX)
+    }
+}
+
+/// Whether two sets of length `len` hold the same bits, ignoring anything past `len`.
+fn words_eq(len: usize, lhs: &[u64], rhs: &[u64]) -> bool {
+    match (lhs.split_last(), rhs.split_last()) {
+        (Some((&lhs_tail, lhs)), Some((&rhs_tail, rhs))) => {
+            words::eq(lhs, rhs) && (lhs_tail ^ rhs_tail) & tail_mask(len) == 0
+        }
+        _ => lhs.is_empty() && rhs.is_empty(),
+    }
 }
 
 macro_rules! bitwise_impl {
-    ($trait:ident, $op:ident, $binop:ident) => {
+    ($trait:ident, $op:ident, $optrait:ident, $binop:ident, $kernel:ident, $kernel3:ident) => {
         impl<'a> $trait<&'a Bitset> for Bitset {
             fn $op(&mut self, rhs: &'a Self) {
                 assert_eq!(self.len, rhs.len);
-                for i in 0..self.bits.len() {
-                    unsafe { u64::$op(self.bits.get_unchecked_mut(i), *rhs.bits.get_unchecked(i)) };
-                }
+                super::words::$kernel(self.bits_mut(), rhs.bits());
+            }
+        }
+
+        impl<'a> $optrait<&'a Bitset> for &'a Bitset {
+            type Output = Bitset;
+            fn $binop(self, rhs: &'a Bitset) -> Bitset {
+                let mut out = self.clone();
+                $trait::$op(&mut out, rhs);
+                out
             }
         }
 
@@ -193,22 +1276,60 @@ macro_rules! bitwise_impl {
             pub fn $binop(out: &mut Bitset, lhs: &Bitset, rhs: &Bitset) {
                 assert_eq!(out.len, lhs.len);
                 assert_eq!(out.len, rhs.len);
-                for i in 0..out.bits.len() {
-                    unsafe {
-                        *out.bits.get_unchecked_mut(i) =
-                            u64::$binop(*lhs.bits.get_unchecked(i), *lhs.bits.get_unchecked(i));
-                    };
-                }
+                super::words::$kernel3(out.bits_mut(), lhs.bits(), rhs.bits());
+            }
+        }
+    };
+}
+
+macro_rules! fixed_bitwise_impl {
+    ($trait:ident, $op:ident, $optrait:ident, $binop:ident, $kernel:ident) => {
+        impl<const BITS: usize, const WORDS: usize> $trait<&FixedBitset<BITS, WORDS>>
+            for FixedBitset<BITS, WORDS>
+        {
+            #[inline]
+            fn $op(&mut self, rhs: &Self) {
+                super::words::$kernel(&mut self.bits, &rhs.bits);
+            }
+        }
+
+        impl<const BITS: usize, const WORDS: usize> $optrait for FixedBitset<BITS, WORDS> {
+            type Output = Self;
+            #[inline]
+            fn $binop(mut self, rhs: Self) -> Self {
+                $trait::$op(&mut self, &rhs);
+                self
             }
         }
     };
 }
 
 mod ops {
-    use super::Bitset;
-    use std::ops::{BitAnd, BitOr, BitXor};
+    use super::{Bits, Bitset, FixedBitset};
+    use std::ops::{BitAnd, BitOr, BitXor, Not};
     use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign};
-    bitwise_impl!(BitAndAssign, bitand_assign, bitand);
-    bitwise_impl!(BitOrAssign, bitor_assign, bitor);
-    bitwise_impl!(BitXorAssign, bitxor_assign, bitxor);
+    bitwise_impl!(BitAndAssign, bitand_assign, BitAnd, bitand, and_assign, and);
+    bitwise_impl!(BitOrAssign, bitor_assign, BitOr, bitor, or_assign, or);
+    bitwise_impl!(BitXorAssign, bitxor_assign, BitXor, bitxor, xor_assign, xor);
+
+    impl Not for &Bitset {
+        type Output = Bitset;
+        fn not(self) -> Bitset {
+            let mut out = self.clone();
+            out.not_assign();
+            out
+        }
+    }
+
+    fixed_bitwise_impl!(BitAndAssign, bitand_assign, BitAnd, bitand, and_assign);
+    fixed_bitwise_impl!(BitOrAssign, bitor_assign, BitOr, bitor, or_assign);
+    fixed_bitwise_impl!(BitXorAssign, bitxor_assign, BitXor, bitxor, xor_assign);
+
+    impl<const BITS: usize, const WORDS: usize> Not for FixedBitset<BITS, WORDS> {
+        type Output = Self;
+        fn not(mut self) -> Self {
+            self.not_assign();
+            self
+        }
+    }
 }
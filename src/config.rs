@@ -0,0 +1,151 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde::Deserialize;
+
+use crate::{inputs::InputLayout, Puzzle, HOME_YEAR};
+
+/// Project-wide defaults for the runner, read from `aoc.toml`. Anything given on the command line
+/// wins over what's in here.
+///
+/// ```toml
+/// year = 2024
+/// sample_count = 10
+/// rerun_time_limit_s = 30
+/// output = "pretty"
+///
+/// [inputs]
+/// dir = "inputs"
+/// path = "{year}/day{day}"
+///
+/// [days.d7]
+/// sample_count = 100
+///
+/// [days."2023.d7"]
+/// rerun_time_limit_s = 5
+/// ```
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub year: Option<u32>,
+    pub sample_count: Option<usize>,
+    pub rerun_time_limit_s: Option<f64>,
+    pub output: Option<OutputFormat>,
+    #[serde(default)]
+    pub inputs: InputLayout,
+    /// Overrides for single days, keyed like `d7`, or `2023.d7` for a day from another event.
+    #[serde(default)]
+    pub days: BTreeMap<String, DayConfig>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DayConfig {
+    pub sample_count: Option<usize>,
+    pub rerun_time_limit_s: Option<f64>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Colored summaries meant for people.
+    #[default]
+    Pretty,
+    /// A line of JSON per result, in the same shape as `--export` writes.
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            _ => eyre::bail!("unknown output format '{s}', expected 'pretty' or 'json'"),
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Reads the config at `path`, or the defaults if there isn't one.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        if !std::fs::exists(path)? {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)?;
+        let config = Self::parse(&text).map_err(|err| eyre::eyre!("{}: {err}", path.display()))?;
+        Ok(config)
+    }
+
+    pub fn parse(text: &str) -> eyre::Result<Self> {
+        let config: Self = toml::from_str(text)?;
+        // catch typos in the keys now, rather than silently never applying the override.
+        for key in config.days.keys() {
+            Puzzle::parse(key, HOME_YEAR)?;
+        }
+        if !config.inputs.path.contains("{day}") {
+            eyre::bail!("inputs.path must contain '{{day}}'");
+        }
+        Ok(config)
+    }
+
+    /// The overrides for `puzzle`, where days without a year belong to `default_year`.
+    pub fn day(&self, puzzle: Puzzle, default_year: u32) -> Option<&DayConfig> {
+        self.days
+            .iter()
+            .find(|(key, _)| Puzzle::parse(key, default_year).ok() == Some(puzzle))
+            .map(|(_, config)| config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = ProjectConfig::parse(
+            r#"
+            sample_count = 10
+            output = "json"
+
+            [inputs]
+            dir = "puzzles"
+
+            [days.d7]
+            sample_count = 100
+
+            [days."2023.d7"]
+            rerun_time_limit_s = 5
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.sample_count, Some(10));
+        assert_eq!(config.output, Some(OutputFormat::Json));
+        assert_eq!(config.inputs.dir, Path::new("puzzles"));
+        assert_eq!(config.inputs.path, InputLayout::default().path);
+
+        let day7 = |year| Puzzle { year, day: 7 };
+        assert_eq!(
+            config.day(day7(2024), 2024).unwrap().sample_count,
+            Some(100)
+        );
+        assert_eq!(
+            config.day(day7(2025), 2025).unwrap().sample_count,
+            Some(100)
+        );
+        assert_eq!(
+            config.day(day7(2023), 2024).unwrap().rerun_time_limit_s,
+            Some(5.0)
+        );
+        assert!(config.day(Puzzle { year: 2024, day: 8 }, 2024).is_none());
+    }
+
+    #[test]
+    fn test_reject_bad_config() {
+        assert!(ProjectConfig::parse("sample_cuont = 10").is_err());
+        assert!(ProjectConfig::parse("[days.seven]\nsample_count = 1").is_err());
+        assert!(ProjectConfig::parse("[inputs]\npath = \"{year}\"").is_err());
+    }
+}
@@ -4,11 +4,6 @@ use eyre::OptionExt;
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 struct Lists {
     left: Vec<i32>,
     right: Vec<i32>,
@@ -31,6 +26,7 @@ fn parse(ctx: &mut RunContext) -> eyre::Result<Lists> {
     })
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut input = parse(ctx)?;
 
@@ -45,6 +41,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(total_dist)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let input = parse(ctx)?;
 
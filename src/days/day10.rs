@@ -1,23 +1,19 @@
+#[cfg(feature = "nightly-simd")]
 use std::simd::{cmp::SimdPartialEq, u8x32};
 
 use crate::{bitset::Bitset, prelude::*};
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-    repo.add_variant("part2_no_recursion", part2_no_recursion);
-    repo.add_variant("part2_no_recursion_flat", part2_no_recursion_flat);
-    repo.add_variant("part2_no_recursion_flat_dir", part2_no_recursion_flat_dir);
-    repo.add_variant(
-        "part2_no_recursion_flat_unsafe",
-        part2_no_recursion_flat_unsafe,
-    );
-}
-
 const MAP_WIDTH: usize = 55;
 // const MAP_WIDTH: usize = 8;
 const MAP_STRIDE: usize = MAP_WIDTH + 1;
 
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.grid(MAP_WIDTH, MAP_WIDTH, |ch| ch.is_ascii_digit())?;
+    validator.end()
+}
+
 fn trailhead_score(map: &[u8], visited: &mut Bitset, x: usize, y: usize, cur: u8) -> usize {
     if cur == b'9' {
         let seen = visited.get(MAP_WIDTH * y + x);
@@ -43,6 +39,7 @@ fn trailhead_score(map: &[u8], visited: &mut Bitset, x: usize, y: usize, cur: u8
     score
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut visited = Bitset::new(MAP_WIDTH * MAP_WIDTH);
     let mut sum = 0;
@@ -50,7 +47,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         for x in 0..MAP_WIDTH {
             if ctx.input_scratch[MAP_STRIDE * y + x] == b'0' {
                 visited.clear_all();
-                sum += trailhead_score(ctx.input_scratch, &mut visited, x, y, b'0');
+                sum += trailhead_score(&ctx.input_scratch, &mut visited, x, y, b'0');
             }
         }
     }
@@ -79,18 +76,20 @@ fn trailhead_rating(map: &[u8], x: usize, y: usize, cur: u8) -> usize {
     score
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut sum = 0;
     for y in 0..MAP_WIDTH {
         for x in 0..MAP_WIDTH {
             if ctx.input_scratch[MAP_STRIDE * y + x] == b'0' {
-                sum += trailhead_rating(ctx.input_scratch, x, y, b'0');
+                sum += trailhead_rating(&ctx.input_scratch, x, y, b'0');
             }
         }
     }
     Ok(sum)
 }
 
+#[variant]
 fn part2_no_recursion(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut sum = 0;
     let mut stack = vec![];
@@ -124,6 +123,20 @@ fn part2_no_recursion(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(sum)
 }
 
+/// The offset of the first trailhead in a 32-byte chunk of the map.
+#[cfg(feature = "nightly-simd")]
+#[inline(always)]
+fn find_trailhead(chunk: &[u8]) -> Option<usize> {
+    const ZERO_CHUNK: u8x32 = u8x32::from_array([b'0'; 32]);
+    u8x32::from_slice(chunk).simd_eq(ZERO_CHUNK).first_set()
+}
+
+#[cfg(not(feature = "nightly-simd"))]
+#[inline(always)]
+fn find_trailhead(chunk: &[u8]) -> Option<usize> {
+    chunk[..32].iter().position(|&ch| ch == b'0')
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 enum Direction {
     None,
@@ -133,16 +146,15 @@ enum Direction {
     Left,
 }
 
+#[variant]
 fn part2_no_recursion_flat(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut sum = 0;
     let mut stack = Vec::with_capacity(256);
 
-    const ZERO_CHUNK: u8x32 = u8x32::from_array([b'0'; 32]);
-
     let mut ix = 0;
     while ix < ctx.input_scratch.len() {
-        let chunk = u8x32::load_or_default(&ctx.input_scratch[ix..]);
-        if let Some(off) = chunk.simd_eq(ZERO_CHUNK).first_set() {
+        // the scratch buffer is padded, so this can't read past its end.
+        if let Some(off) = find_trailhead(&ctx.input_scratch.padded()[ix..ix + 32]) {
             ix += off;
         } else {
             ix += 32;
@@ -182,16 +194,14 @@ fn part2_no_recursion_flat(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(sum)
 }
 
+#[variant]
 fn part2_no_recursion_flat_dir(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut sum = 0;
     let mut stack = Vec::with_capacity(256);
 
-    const ZERO_CHUNK: u8x32 = u8x32::from_array([b'0'; 32]);
-
     let mut ix = 0;
     while ix < ctx.input_scratch.len() {
-        let chunk = u8x32::load_or_default(&ctx.input_scratch[ix..]);
-        if let Some(off) = chunk.simd_eq(ZERO_CHUNK).first_set() {
+        if let Some(off) = find_trailhead(&ctx.input_scratch.padded()[ix..ix + 32]) {
             ix += off;
         } else {
             ix += 32;
@@ -234,16 +244,15 @@ fn part2_no_recursion_flat_dir(ctx: &mut RunContext) -> eyre::Result<impl Displa
     Ok(sum)
 }
 
+#[variant]
 fn part2_no_recursion_flat_unsafe(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut sum = 0;
     let mut stack = Vec::with_capacity(512);
 
-    const ZERO_CHUNK: u8x32 = u8x32::from_array([b'0'; 32]);
-
     let mut ix = 0;
     while ix < ctx.input_scratch.len() {
-        let chunk = u8x32::load_or_default(unsafe { ctx.input_scratch.get_unchecked(ix..) });
-        if let Some(off) = chunk.simd_eq(ZERO_CHUNK).first_set() {
+        let padded = ctx.input_scratch.padded();
+        if let Some(off) = find_trailhead(unsafe { padded.get_unchecked(ix..ix + 32) }) {
             ix += off;
         } else {
             ix += 32;
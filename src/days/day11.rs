@@ -2,13 +2,6 @@ use ahash::AHashMap;
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part1_cached", part1_cached);
-    repo.add_variant("part2", part2);
-    repo.add_variant("generate_lut", generate_lut);
-}
-
 fn split_digits(n: u64) -> Option<(u64, u64)> {
     let mut k = 0;
     let mut t = n;
@@ -25,6 +18,7 @@ fn split_digits(n: u64) -> Option<(u64, u64)> {
     Some((n / p, n % p))
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut front = vec![];
     let mut back = vec![];
@@ -82,6 +76,7 @@ fn seq_count<const MAX_DEPTH: u8>(cache: &mut AHashMap<u64, u64>, num: u64, dept
     res
 }
 
+#[variant]
 fn part1_cached(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut sum = 0;
     let mut cache = AHashMap::with_capacity(200_000);
@@ -91,6 +86,7 @@ fn part1_cached(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(sum)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut sum = 0;
     let mut cache = AHashMap::with_capacity(200_000);
@@ -180,6 +176,7 @@ const LUT75: [[u64; 100]; 75] = [
     [1,1,1,1,1,1,1,1,1,1,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,2,],
 ];
 
+#[variant]
 fn generate_lut(_ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut cache = AHashMap::with_capacity(200_000);
 
@@ -1,14 +1,17 @@
 use crate::{bitset::Bitset, prelude::*};
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 const MAP_SIZE: usize = 140;
 // const MAP_SIZE: usize = 10;
 const MAP_STRIDE: usize = MAP_SIZE + 1;
 
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.grid(MAP_SIZE, MAP_SIZE, |ch| ch.is_ascii_uppercase())?;
+    validator.end()
+}
+
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<u64> {
     let mut sum = 0;
     let mut visited = Bitset::new(MAP_STRIDE * MAP_STRIDE);
@@ -156,6 +159,7 @@ const DOWN: usize = 1;
 // const LEFT: usize = 2;
 const UP: usize = 3;
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<u64> {
     let mut counted_perims = [
         Bitset::new(MAP_STRIDE * MAP_STRIDE), // r
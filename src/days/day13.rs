@@ -1,8 +1,18 @@
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
+// `run_part` reads the button offsets as two digit numbers at fixed positions.
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    loop {
+        validator.line("Button A: X+{d}{d}, Y+{d}{d}")?;
+        validator.line("Button B: X+{d}{d}, Y+{d}{d}")?;
+        validator.line("Prize: X={n}, Y={n}")?;
+        if !validator.has_more() {
+            return Ok(());
+        }
+        validator.blank()?;
+    }
 }
 
 fn parse_number(input: &str, cur: &mut usize) -> eyre::Result<i64> {
@@ -55,10 +65,12 @@ fn run_part(ctx: &mut RunContext, offset: i64) -> eyre::Result<impl Display> {
     Ok(sum)
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     run_part(ctx, 0)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     run_part(ctx, 10000000000000)
 }
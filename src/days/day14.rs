@@ -1,9 +1,4 @@
-use crate::{bitset::Bitset, prelude::*};
-
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
+use crate::{bitgrid::BitGrid, bitset::Bitset, prelude::*};
 
 fn parse_number(text: &[u8], cur: &mut usize) -> i32 {
     let mut res = 0;
@@ -23,6 +18,16 @@ fn parse_number(text: &[u8], cur: &mut usize) -> i32 {
 const MAP_WIDTH: i32 = 101;
 const MAP_HEIGHT: i32 = 103;
 
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.each_line("p={i},{i} v={i},{i}", |fields| {
+        fields[0].in_range(0..MAP_WIDTH as i64, "x position")?;
+        fields[1].in_range(0..MAP_HEIGHT as i64, "y position")
+    })
+}
+
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut q1 = 0;
     let mut q2 = 0;
@@ -113,43 +118,27 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
 // #                             #
 // ###############################
 
-// const TREE_WIDTH: usize = 31;
-// const TREE_HEIGHT: usize = 33;
-// const TREE_BITS: [u64; 16] = [
-//     0xffffffff00000006,
-//     0x0000000c00000018,
-//     0x0000003000200060,
-//     0x00e000c003e00180,
-//     0x0fe003003fe00600,
-//     0x1f000c007f001801,
-//     0xff003007ff00601f,
-//     0xff00c00ff801803f,
-//     0xf80300fff80603ff,
-//     0xf80c0ffff81807ff,
-//     0xc0301fffc0607fff,
-//     0xc0c1ffffc187ffff,
-//     0xc300070006000e00,
-//     0x0c001c0018000000,
-//     0x3000000060000000,
-//     0xc0000001fffffffe,
-// ];
+const TREE_WIDTH: usize = 31;
+const TREE_HEIGHT: usize = 33;
+// the picture above, as `Bitset::from_hex` reads it.
+const TREE_HEX: &str = "
+    ffffffff00000006 0000000c00000018 0000003000200060 00e000c003e00180
+    0fe003003fe00600 1f000c007f001801 ff003007ff00601f ff00c00ff801803f
+    f80300fff80603ff f80c0ffff81807ff c0301fffc0607fff c0c1ffffc187ffff
+    c300070006000e00 0c001c0018000000 3000000060000000 c0000001fffffffe
+";
 
+const PALETTE: &[Rgb] = &[[8, 16, 8], [60, 220, 80]];
+
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
-    #[derive(Copy, Clone, Debug)]
-    struct Robot {
-        x: i32,
-        y: i32,
-        vx: i32,
-        vy: i32,
-    }
+    let tree = Bitset::from_hex(TREE_WIDTH * TREE_HEIGHT, TREE_HEX)?;
+    let tree = BitGrid::from_bitset(&tree, TREE_WIDTH);
 
-    let mut map = Bitset::new((MAP_WIDTH * MAP_HEIGHT) as usize);
     let mut robots = vec![];
-
     for line in ctx.input.lines() {
         let line = line.as_bytes();
-        let mut cur = 0;
-        cur += 2; // "p="
+        let mut cur = 2; // "p="
         let x = parse_number(line, &mut cur);
         cur += 1; // ","
         let y = parse_number(line, &mut cur);
@@ -157,51 +146,46 @@ fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         let vx = parse_number(line, &mut cur);
         cur += 1; // ","
         let vy = parse_number(line, &mut cur);
-
-        robots.push(Robot { x, y, vx, vy });
+        robots.push([x, y, vx, vy]);
     }
 
-    for step in 0..10000 {
+    // the picture repeats once every robot is back where it started.
+    let mut map = BitGrid::new(MAP_WIDTH as usize, MAP_HEIGHT as usize);
+    for step in 0..MAP_WIDTH * MAP_HEIGHT {
         map.clear_all();
-        for robot in &mut robots {
-            map.set((MAP_WIDTH * robot.y + robot.x) as usize);
-            robot.x += robot.vx;
-            robot.y += robot.vy;
-
-            if robot.x >= MAP_WIDTH {
-                robot.x -= MAP_WIDTH;
-            }
-            if robot.x < 0 {
-                robot.x += MAP_WIDTH;
-            }
-            if robot.y >= MAP_HEIGHT {
-                robot.y -= MAP_HEIGHT;
-            }
-            if robot.y < 0 {
-                robot.y += MAP_HEIGHT;
-            }
+        for &[x, y, vx, vy] in &robots {
+            let x = (x + vx * step).rem_euclid(MAP_WIDTH);
+            let y = (y + vy * step).rem_euclid(MAP_HEIGHT);
+            map.set(x as usize, y as usize);
         }
-
-        let mut contig = 0;
-        let mut prev = false;
-        for i in 0..(MAP_WIDTH * MAP_HEIGHT) as usize {
-            let cur = map.get(i);
-            if prev && cur {
-                contig += 1;
-            } else {
-                contig = 0;
-            }
-
-            if contig >= 30 {
-                println!(
-                    "{step}:\n{:?}",
-                    crate::bitset::DebugBitset(&map, MAP_WIDTH as usize, MAP_HEIGHT as usize)
-                );
-            }
-
-            prev = cur;
+        // a little slack, in case a stray robot happens to be somewhere in the frame.
+        if map.find_pattern(&tree, 8).next().is_some() {
+            ctx.visualizer.frame(|| {
+                Frame::from_fn(map.width(), map.height(), PALETTE, |x, y| {
+                    map.get(x, y) as u8
+                })
+            });
+            return Ok(step);
         }
     }
 
-    todo!() as eyre::Result<i32>
+    eyre::bail!("the tree never showed up")
+}
+
+#[test]
+fn test_tree() {
+    let tree = Bitset::from_hex(TREE_WIDTH * TREE_HEIGHT, TREE_HEX).unwrap();
+    let hex = TREE_HEX.split_whitespace().collect::<String>();
+    assert_eq!(tree.to_hex(), hex);
+
+    // the frame around it, and the trunk at the bottom.
+    let tree = BitGrid::from_bitset(&tree, TREE_WIDTH);
+    for x in 0..TREE_WIDTH {
+        assert!(tree.get(x, 0) && tree.get(x, TREE_HEIGHT - 1));
+    }
+    for y in 0..TREE_HEIGHT {
+        assert!(tree.get(0, y) && tree.get(TREE_WIDTH - 1, y));
+    }
+    assert!((14..17).all(|x| tree.get(x, 25) && tree.get(x, 27)));
+    assert!(!tree.get(13, 26) && !tree.get(17, 26));
 }
@@ -1,10 +1,5 @@
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 fn memchr(haystack: &[u8], needle: u8) -> Option<usize> {
     haystack.iter().copied().position(|ch| ch == needle)
 }
@@ -12,6 +7,28 @@ fn memchr(haystack: &[u8], needle: u8) -> Option<usize> {
 const MAP_WIDTH: usize = 50;
 const MAP_STRIDE: usize = MAP_WIDTH + 1;
 
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.grid(MAP_WIDTH, MAP_WIDTH, |ch| b"#.O@".contains(&ch))?;
+    validator.blank()?;
+    validator.each_line_chars(|ch| b"^>v<".contains(&ch))
+}
+
+const PALETTE: &[Rgb] = &[[16, 16, 24], [90, 90, 110], [200, 140, 60], [250, 80, 80]];
+
+fn draw_map(map: &[u8]) -> Frame {
+    Frame::from_fn(MAP_WIDTH, MAP_WIDTH, PALETTE, |x, y| {
+        match map[MAP_STRIDE * y + x] {
+            b'#' => 1,
+            b'O' => 2,
+            b'@' => 3,
+            _ => 0,
+        }
+    })
+}
+
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut i = 0;
     while i + 1 < ctx.input_scratch.len() {
@@ -24,6 +41,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let (map, insns) = ctx.input_scratch.split_at_mut(i + 1);
 
     let mut ix = memchr(map, b'@').unwrap();
+    ctx.visualizer.frame(|| draw_map(map));
     for &mut insn in insns {
         let offset = match insn {
             b'^' => -(MAP_STRIDE as isize),
@@ -57,6 +75,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
                 ix = neighbor_ix;
             }
         }
+        ctx.visualizer.frame(|| draw_map(map));
     }
 
     let mut res = 0;
@@ -128,6 +147,7 @@ fn push_block(map: &mut [u8], pos: usize, dir: isize) {
     map[pos] = TILE_EMPTY;
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut map = vec![];
 
@@ -1,9 +1,4 @@
-use crate::{bitset::Bitset, prelude::*};
-
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
+use crate::{bitset::Bitset, prelude::*, visualize::Visualizer};
 
 fn pathfind_dfs(
     walls: &Bitset,
@@ -48,6 +43,14 @@ fn pathfind_dfs(
 const MAP_WIDTH: usize = 141;
 const OFFSETS: [isize; 4] = [1, MAP_WIDTH as isize, -1, -(MAP_WIDTH as isize)];
 
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.grid(MAP_WIDTH, MAP_WIDTH, |ch| b"#.SE".contains(&ch))?;
+    validator.end()
+}
+
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut cost_map = vec![u32::MAX; MAP_WIDTH * MAP_WIDTH];
     let mut map = Bitset::new(MAP_WIDTH * MAP_WIDTH);
@@ -117,14 +120,38 @@ fn pathfind_dfs_directional(
     }
 }
 
+const PALETTE: &[Rgb] = &[[16, 16, 24], [90, 90, 110], [40, 60, 90], [250, 200, 40]];
+
+/// The walls, every tile the search reached, and the best paths marked so far.
+fn draw_maze(walls: &Bitset, cost_map: &[[u32; 4]], best_path: &Bitset) -> Frame {
+    Frame::from_fn(MAP_WIDTH, MAP_WIDTH, PALETTE, |x, y| {
+        let pos = MAP_WIDTH * y + x;
+        if walls.get(pos) {
+            1
+        } else if best_path.get(pos) {
+            3
+        } else if cost_map[pos].iter().any(|&cost| cost != u32::MAX) {
+            2
+        } else {
+            0
+        }
+    })
+}
+
 fn mark_best_path(
+    walls: &Bitset,
     cost_map: &[[u32; 4]],
     best_path: &mut Bitset,
+    visualizer: &mut Visualizer,
     pos: usize,
     dir: usize,
     goal: usize,
 ) {
-    best_path.set(pos);
+    // turning marks the same tile again, which isn't worth another frame.
+    if !best_path.get(pos) {
+        best_path.set(pos);
+        visualizer.frame(|| draw_maze(walls, cost_map, best_path));
+    }
     if pos == goal {
         return;
     }
@@ -134,19 +161,20 @@ fn mark_best_path(
     let back_dir = (dir + 2) & 3;
     let back_pos = pos.wrapping_add_signed(OFFSETS[back_dir]);
     if cost_map[back_pos][dir] + 1 == cur {
-        mark_best_path(cost_map, best_path, back_pos, dir, goal);
+        mark_best_path(walls, cost_map, best_path, visualizer, back_pos, dir, goal);
     }
 
     let cw_dir = dir.wrapping_add(1) & 3;
     if cost_map[pos][cw_dir] + 1000 == cur {
-        mark_best_path(cost_map, best_path, pos, cw_dir, goal);
+        mark_best_path(walls, cost_map, best_path, visualizer, pos, cw_dir, goal);
     }
     let ccw_dir = dir.wrapping_sub(1) & 3;
     if cost_map[pos][ccw_dir] + 1000 == cur {
-        mark_best_path(cost_map, best_path, pos, ccw_dir, goal);
+        mark_best_path(walls, cost_map, best_path, visualizer, pos, ccw_dir, goal);
     }
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut cost_map = vec![[u32::MAX; 4]; MAP_WIDTH * MAP_WIDTH];
     let mut map = Bitset::new(MAP_WIDTH * MAP_WIDTH);
@@ -167,10 +195,20 @@ fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     }
 
     pathfind_dfs_directional(&map, &mut cost_map, start_pos, 0, goal_pos, 0);
+    ctx.visualizer
+        .frame(|| draw_maze(&map, &cost_map, &best_path));
     let goal_min = cost_map[goal_pos].iter().copied().min().unwrap();
     for i in 0..4 {
         if cost_map[goal_pos][i] == goal_min {
-            mark_best_path(&cost_map, &mut best_path, goal_pos, i, start_pos);
+            mark_best_path(
+                &map,
+                &cost_map,
+                &mut best_path,
+                &mut ctx.visualizer,
+                goal_pos,
+                i,
+                start_pos,
+            );
         }
     }
 
@@ -1,10 +1,5 @@
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 fn parse_number(input: &[u8], cur: &mut usize) -> u64 {
     let mut i = *cur;
     let mut res = 0;
@@ -30,6 +25,34 @@ const REG_A: usize = 0;
 const REG_B: usize = 1;
 const REG_C: usize = 2;
 
+// `parse_state` skips over the fixed-length labels, and reads the program as single digits.
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.line("Register A: {n}")?;
+    validator.line("Register B: {n}")?;
+    validator.line("Register C: {n}")?;
+    validator.blank()?;
+    validator.line_with("the program", |line| {
+        let Some(program) = line.strip_prefix("Program: ") else {
+            return Err((0, "expected `Program: `".into()));
+        };
+        for (i, ch) in program.bytes().enumerate() {
+            let column = "Program: ".len() + i;
+            match (i % 2, ch) {
+                (0, b'0'..=b'7') | (1, b',') => {}
+                (0, _) => return Err((column, "expected an octal digit".into())),
+                _ => return Err((column, "expected ','".into())),
+            }
+        }
+        match program.len() % 4 {
+            3 => Ok(()),
+            _ => Err((line.len(), "expected an even number of values".into())),
+        }
+    })?;
+    validator.end()
+}
+
 struct State {
     ip: usize,
     registers: [u64; 3],
@@ -138,6 +161,7 @@ fn parse_state(input: &[u8], state: &mut State) {
     }
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut state = State::default();
     parse_state(&ctx.input_scratch, &mut state);
@@ -214,6 +238,7 @@ fn test_candidate(program: &[u8], n: usize, cur_a: u64) -> Option<u64> {
     None
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut state = State::default();
     parse_state(&ctx.input_scratch, &mut state);
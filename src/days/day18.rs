@@ -1,18 +1,23 @@
 use std::{collections::VecDeque, fmt::Display};
 
-use crate::{bitset::Bitset, prelude::*};
-
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
+use crate::{bitgrid::BitGrid, bitset::Bitset, prelude::*};
 
 const MAP_WIDTH: usize = 71;
 
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.each_line("{n},{n}", |fields| {
+        fields[0].in_range(0..MAP_WIDTH as i64, "x")?;
+        fields[1].in_range(0..MAP_WIDTH as i64, "y")
+    })
+}
+
 fn idx(x: usize, y: usize) -> usize {
     MAP_WIDTH * y + x
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut obstacle_map = Bitset::new(MAP_WIDTH * MAP_WIDTH);
     let mut weights = vec![u32::MAX; MAP_WIDTH * MAP_WIDTH];
@@ -32,6 +37,21 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(answer)
 }
 
+#[variant]
+fn part1_bitgrid(ctx: &mut RunContext) -> eyre::Result<impl Display> {
+    let mut open = BitGrid::from_fn(MAP_WIDTH, MAP_WIDTH, |_, _| true);
+    for line in ctx.input.lines().take(1024) {
+        let Some((x, y)) = line.split_once(',') else {
+            eyre::bail!("invalid input");
+        };
+        open.clear(x.parse()?, y.parse()?);
+    }
+
+    let goal = (MAP_WIDTH - 1, MAP_WIDTH - 1);
+    let answer = open.bfs_distance((0, 0), goal).unwrap_or(u32::MAX);
+    Ok(answer)
+}
+
 fn do_search(obstacles: &Bitset, weights: &mut [u32], queue: &mut VecDeque<(u8, u8, u32)>) {
     weights.fill(u32::MAX);
     queue.clear();
@@ -65,6 +85,7 @@ fn set_obstacles(obstacle_list: &[[u8; 2]], obstacles: &mut Bitset, limit: usize
     }
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut obstacles = Bitset::new(MAP_WIDTH * MAP_WIDTH);
     let mut weights = vec![u32::MAX; MAP_WIDTH * MAP_WIDTH];
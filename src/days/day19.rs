@@ -2,11 +2,6 @@ use ahash::{HashMap, HashMapExt};
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 // white (w), blue (u), black (b), red (r), or green (g)
 
 #[derive(Debug, Default)]
@@ -111,6 +106,7 @@ fn count_permutations(cache: &mut HashMap<usize, usize>, input: &[u8], offset: u
     total
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut node = Node::default();
 
@@ -134,6 +130,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(res)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut node = Node::default();
 
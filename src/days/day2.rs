@@ -2,11 +2,6 @@ use std::fmt::Display;
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 fn part1_verify(line: &[u32]) -> bool {
     assert!(line.len() >= 2);
 
@@ -26,6 +21,7 @@ fn part1_verify(line: &[u32]) -> bool {
     true
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut safe_count = 0;
     let mut line_data = Vec::<u32>::new();
@@ -79,6 +75,7 @@ fn test_day2_part2() {
     assert_eq!(part2_verify(&[1, 3, 6, 7, 9]), true); //: Safe without removing any level.
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut safe_count = 0;
     let mut line_data = Vec::<u32>::new();
@@ -2,13 +2,15 @@ use std::collections::VecDeque;
 
 use crate::{bitset::Bitset, prelude::*};
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 const MAP_SIZE: usize = 141;
 
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.grid(MAP_SIZE, MAP_SIZE, |ch| b"#.SE".contains(&ch))?;
+    validator.end()
+}
+
 fn idx(x: usize, y: usize) -> usize {
     MAP_SIZE * y + x
 }
@@ -82,6 +84,7 @@ fn count_shortcuts_part2(
     res
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let (mut start, mut end) = (0, 0);
     let mut walls = Bitset::new(MAP_SIZE * MAP_SIZE);
@@ -147,6 +150,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(res)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let (mut start, mut end) = (0, 0);
     let mut walls = Bitset::new(MAP_SIZE * MAP_SIZE);
@@ -2,11 +2,6 @@ use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
 fn step_prng(state: u32) -> u32 {
     let mut state = state;
     state ^= state << 6;
@@ -18,6 +13,7 @@ fn step_prng(state: u32) -> u32 {
     state
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<u64> {
     let mut res = 0;
     for line in ctx.input.lines() {
@@ -34,6 +30,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<u64> {
     Ok(res)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<u64> {
     let mut seq_prices = HashMap::new();
     let mut seen_per_buyer = HashSet::new();
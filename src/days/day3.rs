@@ -2,11 +2,7 @@ use std::fmt::Display;
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let regex = regex::Regex::new(r#"mul\(([0-9]{0,3}),([0-9]{0,3})\)"#).unwrap();
     let mut sum = 0;
@@ -53,6 +49,7 @@ fn parse_mul(src: &mut &str) -> Option<u32> {
     Some(a * b)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut src = ctx.input;
 
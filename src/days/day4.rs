@@ -2,12 +2,6 @@ use std::ops::Index;
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part1_split", part1_split);
-    repo.add_variant("part2", part2);
-}
-
 struct Board {
     width: usize,
     height: usize,
@@ -133,6 +127,7 @@ fn check_surrounding(board: &Board, x: usize, y: usize) -> usize {
     res
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let board = parse_board(ctx)?;
 
@@ -146,6 +141,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(res)
 }
 
+#[variant]
 fn part1_split(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let board = parse_board(ctx)?;
 
@@ -237,6 +233,7 @@ fn check_surrounding_part2(board: &Board, x: usize, y: usize) -> usize {
     res
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let board = parse_board(ctx)?;
 
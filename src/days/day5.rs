@@ -2,11 +2,7 @@ use std::collections::{HashMap, HashSet};
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-}
-
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     // A|B -> for each number N in update, find rules like A|N and check if A was already seen (how to discard non-matching rules?)
 
@@ -78,6 +74,7 @@ fn toposort(
     out.push(current);
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut lines_iter = ctx.input.lines();
 
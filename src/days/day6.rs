@@ -1,18 +1,12 @@
 use std::{
     collections::HashSet,
     ops::{Add, Index, IndexMut, Sub},
-    simd::{cmp::SimdPartialEq, u8x2, u8x32, u8x4, usizex4},
 };
 
-use crate::{bitset::Bitset, prelude::*};
+#[cfg(feature = "nightly-simd")]
+use std::simd::{cmp::SimdPartialEq, u8x2, u8x32, u8x4, usizex4};
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part1_bitset", part1_bitset);
-    repo.add_variant("part1_no_parse_simd", part1_no_parse_simd);
-    repo.add_variant("part2", part2);
-    repo.add_variant("part2_bitset", part2_bitset);
-}
+use crate::{bitset::Bitset, prelude::*};
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 enum Direction {
@@ -200,12 +194,29 @@ fn parse(ctx: &mut RunContext) -> eyre::Result<State> {
     })
 }
 
+const PALETTE: &[Rgb] = &[[16, 16, 24], [90, 90, 110], [60, 140, 220], [250, 200, 40]];
+
+fn draw_state(state: &State) -> Frame {
+    Frame::from_fn(state.width, state.height, PALETTE, |x, y| {
+        if (x as isize, y as isize) == (state.pos.x, state.pos.y) {
+            return 3;
+        }
+        match state.map[(x, y)] {
+            Tile::Empty => 0,
+            Tile::Wall => 1,
+            Tile::Seen => 2,
+        }
+    })
+}
+
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut state = parse(ctx)?;
 
     while state.inbounds(state.pos) {
         assert_ne!(state.map[state.pos], Tile::Wall);
         state.map[state.pos] = Tile::Seen;
+        ctx.visualizer.frame(|| draw_state(&state));
 
         let next_pos = state.pos + offset_from_dir(state.dir);
         if state.is_wall(next_pos) {
@@ -241,31 +252,90 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
 
 const BOARD_LEN: usize = 130;
 const BOARD_AREA: usize = 130 * 130;
+
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.grid(BOARD_LEN, BOARD_LEN, |ch| b".#^".contains(&ch))?;
+    validator.end()
+}
 // const HI64: u64 = 1u64 << 63;
 
+/// The walls in a row of the board, as the bitset words for columns 0-63, 64-127 and 128-129.
+#[cfg(feature = "nightly-simd")]
+#[inline(always)]
+fn wall_masks_simd(row: &[u8]) -> [u64; 3] {
+    // `row` runs on into the scratch padding, so these never go past its end.
+    let chunk1 = u8x32::from_slice(&row[..32]);
+    let chunk2 = u8x32::from_slice(&row[32..64]);
+    let chunk3 = u8x32::from_slice(&row[64..96]);
+    let chunk4 = u8x32::from_slice(&row[96..128]);
+    let chunk5 = u8x2::from_slice(&row[128..130]);
+    let mask1 = (chunk1.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
+    let mask2 = (chunk2.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
+    let mask3 = (chunk3.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
+    let mask4 = (chunk4.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
+    // reversed as a whole word, so columns 128 and 129 land in the top two bits.
+    let mask5 = chunk5
+        .simd_eq(u8x2::splat(b'#'))
+        .to_bitmask()
+        .reverse_bits();
+    [(mask1 << 32) | mask2, (mask3 << 32) | mask4, mask5]
+}
+
+/// [`wall_masks_simd`], a byte at a time.
+#[cfg(any(test, not(feature = "nightly-simd")))]
+#[inline(always)]
+fn wall_masks_scalar(row: &[u8]) -> [u64; 3] {
+    // bitsets are msb-first, so the first column goes in the top bit.
+    let mask = |cols: &[u8]| {
+        cols.iter().enumerate().fold(0, |mask, (i, &ch)| {
+            mask | (((ch == b'#') as u64) << (63 - i))
+        })
+    };
+    [
+        mask(&row[..64]),
+        mask(&row[64..128]),
+        mask(&row[128..BOARD_LEN]),
+    ]
+}
+
+#[cfg(not(feature = "nightly-simd"))]
+use wall_masks_scalar as wall_masks;
+#[cfg(feature = "nightly-simd")]
+use wall_masks_simd as wall_masks;
+
+#[test]
+fn test_wall_masks() {
+    let mut row = [b'.'; BOARD_LEN + 1];
+    for col in [0, 31, 32, 63, 64, 100, 127, 128, 129] {
+        row[col] = b'#';
+    }
+    row[BOARD_LEN] = b'\n';
+    let expected = [
+        1 << 63 | 1 << 32 | 1 << 31 | 1,
+        1 << 63 | 1 << 27 | 1,
+        1 << 63 | 1 << 62,
+    ];
+    assert_eq!(wall_masks_scalar(&row), expected);
+    #[cfg(feature = "nightly-simd")]
+    assert_eq!(wall_masks_simd(&row), expected);
+}
+
+#[variant]
 fn part1_bitset(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut walls = Bitset::new(BOARD_AREA + 64);
     let mut visited = Bitset::new(BOARD_AREA + 64);
 
     let mut n = 0;
 
-    let input = ctx.input.as_bytes();
+    let input = ctx.input_scratch.padded();
     let mut ix = 0;
     while ix < ctx.input.len() {
-        let chunk1 = u8x32::load_or_default(&input[ix..]);
-        let chunk2 = u8x32::load_or_default(&input[ix + 32..]);
-        let chunk3 = u8x32::load_or_default(&input[ix + 64..]);
-        let chunk4 = u8x32::load_or_default(&input[ix + 96..]);
-        let chunk5 = u8x2::load_or_default(&input[ix + 128..]);
-        let mask1 = (chunk1.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
-        let mask2 = (chunk2.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
-        let mask3 = (chunk3.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
-        let mask4 = (chunk4.simd_eq(u8x32::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
-        let mask5 = (chunk5.simd_eq(u8x2::splat(b'#')).to_bitmask() as u32).reverse_bits() as u64;
-
-        walls.set_many(n + 0, (mask1 << 32) | mask2);
-        walls.set_many(n + 64, (mask3 << 32) | mask4);
-        walls.set_many(n + 128, mask5 << 62);
+        let [mask1, mask2, mask3] = wall_masks(&input[ix..]);
+        walls.set_many(n + 0, mask1);
+        walls.set_many(n + 64, mask2);
+        walls.set_many(n + 128, mask3);
 
         n += 130;
         // skip trailing `\n`
@@ -343,6 +413,9 @@ fn part1_bitset(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(total)
 }
 
+// this one is all gathers, there's no point having a scalar version of it.
+#[cfg(feature = "nightly-simd")]
+#[variant]
 fn part1_no_parse_simd(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut visited = Bitset::new(BOARD_AREA + 64);
 
@@ -363,7 +436,7 @@ fn part1_no_parse_simd(ctx: &mut RunContext) -> eyre::Result<impl Display> {
                 let ys = usizex4::from_array([y - 1, y - 2, y - 3, y - 4]);
                 let indices = usizex4::splat(BOARD_LEN + 1) * ys + usizex4::splat(x);
                 let mask =
-                    u8x4::gather_or_default(ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
+                    u8x4::gather_or_default(&ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
                 if let Some(ix) = mask.first_set() {
                     y -= ix;
                     break;
@@ -386,7 +459,7 @@ fn part1_no_parse_simd(ctx: &mut RunContext) -> eyre::Result<impl Display> {
                 let xs = usizex4::from_array([x + 1, x + 2, x + 3, x + 4]);
                 let indices = usizex4::splat((BOARD_LEN + 1) * y) + xs;
                 let mask =
-                    u8x4::gather_or_default(ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
+                    u8x4::gather_or_default(&ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
                 if let Some(ix) = mask.first_set() {
                     x += ix;
                     break;
@@ -409,7 +482,7 @@ fn part1_no_parse_simd(ctx: &mut RunContext) -> eyre::Result<impl Display> {
                 let ys = usizex4::from_array([y + 1, y + 2, y + 3, y + 4]);
                 let indices = usizex4::splat(BOARD_LEN + 1) * ys + usizex4::splat(x);
                 let mask =
-                    u8x4::gather_or_default(ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
+                    u8x4::gather_or_default(&ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
                 if let Some(ix) = mask.first_set() {
                     y += ix;
                     break;
@@ -432,7 +505,7 @@ fn part1_no_parse_simd(ctx: &mut RunContext) -> eyre::Result<impl Display> {
                 let xs = usizex4::from_array([x - 1, x - 2, x - 3, x - 4]);
                 let indices = usizex4::splat((BOARD_LEN + 1) * y) + xs;
                 let mask =
-                    u8x4::gather_or_default(ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
+                    u8x4::gather_or_default(&ctx.input_scratch, indices).simd_eq(u8x4::splat(b'#'));
                 if let Some(ix) = mask.first_set() {
                     x -= ix;
                     break;
@@ -530,6 +603,7 @@ fn part1_no_parse_simd(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(total)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut state = parse(ctx)?;
 
@@ -540,6 +614,7 @@ fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     while state.inbounds(state.pos) {
         assert_ne!(state.map[state.pos], Tile::Wall);
         state.map[state.pos] = Tile::Seen;
+        ctx.visualizer.frame(|| draw_state(&state));
 
         let next_pos = state.pos + offset_from_dir(state.dir);
         if state.is_wall(next_pos) {
@@ -607,6 +682,7 @@ fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(total)
 }
 
+#[variant]
 fn part2_bitset(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut visited = Bitset::new(BOARD_AREA);
 
@@ -709,7 +785,7 @@ fn part2_bitset(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         //             let indices = usizex4::splat(BOARD_LEN + 1)
         //                 * (vy - usizex4::from_array([1, 2, 3, 4]))
         //                 + vx;
-        //             let mask = u8x4::gather_or_default(ctx.input_scratch, indices)
+        //             let mask = u8x4::gather_or_default(&ctx.input_scratch, indices)
         //                 .simd_eq(u8x4::splat(b'#'));
 
         //             if let Some(ix) = mask.first_set() {
@@ -733,7 +809,7 @@ fn part2_bitset(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         //             let (vx, vy) = (usizex4::splat(x), usizex4::splat(y));
         //             let indices =
         //                 usizex4::splat(BOARD_LEN + 1) * vy + vx + usizex4::from_array([1, 2, 3, 4]);
-        //             let mask = u8x4::gather_or_default(ctx.input_scratch, indices)
+        //             let mask = u8x4::gather_or_default(&ctx.input_scratch, indices)
         //                 .simd_eq(u8x4::splat(b'#'));
         //             if let Some(ix) = mask.first_set() {
         //                 x += ix;
@@ -757,7 +833,7 @@ fn part2_bitset(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         //             let indices = usizex4::splat(BOARD_LEN + 1)
         //                 * (vy + usizex4::from_array([1, 2, 3, 4]))
         //                 + vx;
-        //             let mask = u8x4::gather_or_default(ctx.input_scratch, indices)
+        //             let mask = u8x4::gather_or_default(&ctx.input_scratch, indices)
         //                 .simd_eq(u8x4::splat(b'#'));
         //             if let Some(ix) = mask.first_set() {
         //                 y += ix;
@@ -780,7 +856,7 @@ fn part2_bitset(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         //             let (vx, vy) = (usizex4::splat(x), usizex4::splat(y));
         //             let indices =
         //                 usizex4::splat(BOARD_LEN + 1) * vy + vx - usizex4::from_array([1, 2, 3, 4]);
-        //             let mask = u8x4::gather_or_default(ctx.input_scratch, indices)
+        //             let mask = u8x4::gather_or_default(&ctx.input_scratch, indices)
         //                 .simd_eq(u8x4::splat(b'#'));
         //             if let Some(ix) = mask.first_set() {
         //                 x -= ix;
@@ -4,13 +4,6 @@ use rayon::{iter::ParallelIterator, str::ParallelString};
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part1_rev", part1_rev);
-    repo.add_variant("part2", part2);
-    repo.add_variant("part2_parallel", part2_parallel);
-}
-
 fn solve_part1(test_value: u64, acc: u64, parts: &[u64]) -> bool {
     if acc > test_value {
         return false;
@@ -22,6 +15,7 @@ fn solve_part1(test_value: u64, acc: u64, parts: &[u64]) -> bool {
     solve_part1(test_value, acc + head, tail) || solve_part1(test_value, acc * head, tail)
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut total = 0;
     let mut parts = vec![];
@@ -62,6 +56,7 @@ fn solve_part1_rev(test_value: u64, acc: u64, parts: &[u64]) -> bool {
     // solve_part1(test_value, acc + head, tail) || solve_part1(test_value, acc * head, tail)
 }
 
+#[variant]
 fn part1_rev(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut total = 0;
     let mut parts = vec![];
@@ -104,6 +99,7 @@ fn solve_part2(test_value: u64, acc: u64, parts: &[u64]) -> bool {
         || solve_part2(test_value, concat(acc, head), tail)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut total = 0;
     let mut parts = vec![];
@@ -122,6 +118,7 @@ fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(total)
 }
 
+#[variant]
 fn part2_parallel(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut total = AtomicU64::new(0);
     // let mut parts = vec![];
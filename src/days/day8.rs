@@ -1,13 +1,20 @@
 use std::collections::HashMap;
 
-use crate::{bitset::Bitset, prelude::*};
-
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part2", part2);
-    repo.add_variant("part2_no_map", part2_no_map);
+use crate::{
+    bitset::{words_for, FixedBitset},
+    prelude::*,
+};
+
+type Antinodes = FixedBitset<{ 50 * 50 }, { words_for(50 * 50) }>;
+
+#[validator]
+fn validate(input: &str) -> ValidateResult {
+    let mut validator = Validator::new(input);
+    validator.grid(50, 50, |ch| ch == b'.' || ch.is_ascii_alphanumeric())?;
+    validator.end()
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut positions = HashMap::<u8, Vec<(i32, i32)>>::new();
     let mut x = 0;
@@ -23,7 +30,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         }
     }
 
-    let mut antinodes = Bitset::new(50 * 50);
+    let mut antinodes = Antinodes::new();
     for (_, antenna_positions) in &positions {
         for i in 0..antenna_positions.len() {
             for j in i + 1..antenna_positions.len() {
@@ -45,6 +52,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(total)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut positions = HashMap::<u8, Vec<(i32, i32)>>::new();
     let mut x = 0;
@@ -60,7 +68,7 @@ fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         }
     }
 
-    let mut antinodes = Bitset::new(50 * 50);
+    let mut antinodes = Antinodes::new();
     for (_, antenna_positions) in &positions {
         for i in 0..antenna_positions.len() {
             for j in i + 1..antenna_positions.len() {
@@ -94,6 +102,7 @@ struct FixedVec<T, const N: usize> {
     data: [T; N],
 }
 
+#[variant]
 fn part2_no_map(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut chars = FixedVec {
         len: 0,
@@ -131,7 +140,7 @@ fn part2_no_map(ctx: &mut RunContext) -> eyre::Result<impl Display> {
         }
     }
 
-    let mut antinodes = Bitset::new(50 * 50);
+    let mut antinodes = Antinodes::new();
     for k in 0..chars.len {
         let antenna_positions = &positions[k].data[..positions[k].len];
         for i in 0..antenna_positions.len() {
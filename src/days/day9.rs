@@ -2,17 +2,12 @@ use std::collections::VecDeque;
 
 use crate::prelude::*;
 
-pub fn add_variants(repo: &mut RunnerRepository) {
-    repo.add_variant("part1", part1);
-    repo.add_variant("part1_stackless", part1_stackless);
-    repo.add_variant("part2", part2);
-}
-
 #[inline]
 fn checksum_contiguous(id: usize, pos: usize, len: usize) -> usize {
     id * len * (2 * pos + len - 1) / 2
 }
 
+#[variant]
 fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let mut stack = VecDeque::new();
     let input = ctx.input.trim().as_bytes();
@@ -68,6 +63,7 @@ fn part1(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(sum)
 }
 
+#[variant]
 fn part1_stackless(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     let input = ctx.input.trim().as_bytes();
 
@@ -109,6 +105,7 @@ fn part1_stackless(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     Ok(sum)
 }
 
+#[variant]
 fn part2(ctx: &mut RunContext) -> eyre::Result<impl Display> {
     #[derive(Copy, Clone, Debug)]
     struct File {
@@ -0,0 +1,73 @@
+use serde::Serialize;
+
+/// The machine and build a benchmark ran on. Timings are only really comparable between runs that
+/// agree on all of this.
+#[derive(Clone, Debug, Serialize)]
+pub struct RunEnvironment {
+    pub cpu_model: String,
+    pub cores: usize,
+    /// The cpufreq scaling governor, if the platform exposes one.
+    pub governor: Option<String>,
+    pub kernel: String,
+    pub rustc: String,
+    pub profile: String,
+    pub target_features: Vec<String>,
+    pub git_commit: String,
+    /// Whether the working tree had uncommitted changes when the binary was built.
+    pub git_dirty: bool,
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    Some(std::fs::read_to_string(path).ok()?.trim().to_string())
+}
+
+fn cpu_model() -> Option<String> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").ok()?;
+    cpuinfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "model name").then(|| value.trim().to_string())
+    })
+}
+
+impl RunEnvironment {
+    pub fn detect() -> Self {
+        Self {
+            cpu_model: cpu_model().unwrap_or_else(|| "unknown".into()),
+            cores: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            governor: read_trimmed("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor"),
+            kernel: read_trimmed("/proc/sys/kernel/osrelease")
+                .unwrap_or_else(|| std::env::consts::OS.into()),
+            rustc: env!("AOC_RUSTC_VERSION").into(),
+            profile: env!("AOC_BUILD_PROFILE").into(),
+            target_features: env!("AOC_TARGET_FEATURES")
+                .split(',')
+                .filter(|feature| !feature.is_empty())
+                .map(Into::into)
+                .collect(),
+            git_commit: env!("AOC_GIT_COMMIT").into(),
+            git_dirty: env!("AOC_GIT_DIRTY") == "true",
+        }
+    }
+}
+
+impl std::fmt::Display for RunEnvironment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[\x1b[32mcpu\x1b[0m {} ({} cores)] [\x1b[32mgovernor\x1b[0m {}] [\x1b[32mkernel\x1b[0m {}]",
+            self.cpu_model,
+            self.cores,
+            self.governor.as_deref().unwrap_or("n/a"),
+            self.kernel
+        )?;
+        write!(
+            f,
+            "\n[\x1b[32mrustc\x1b[0m {}] [\x1b[32mprofile\x1b[0m {}] [\x1b[32mcommit\x1b[0m {}{}] [\x1b[32mtarget features\x1b[0m {}]",
+            self.rustc,
+            self.profile,
+            self.git_commit,
+            if self.git_dirty { "-dirty" } else { "" },
+            self.target_features.join(",")
+        )
+    }
+}
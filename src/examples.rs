@@ -0,0 +1,104 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// An example input pulled out of a puzzle page, and the answers the page gives for it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Example {
+    pub text: String,
+    pub answers: Vec<(String, String)>,
+}
+
+lazy_static! {
+    static ref ARTICLE: Regex =
+        Regex::new(r#"(?s)<article class="day-desc">(.*?)</article>"#).unwrap();
+    static ref PRE_BLOCK: Regex = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    static ref HIGHLIGHT: Regex =
+        Regex::new(r"<code><em>([^<]*)</em></code>|<em><code>([^<]*)</code></em>").unwrap();
+    static ref TAG: Regex = Regex::new(r"<[^>]*>").unwrap();
+}
+
+fn decode_html(text: &str) -> String {
+    TAG.replace_all(text, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Finds the example blocks on a puzzle page.
+///
+/// Each part's description is its own `<article>`, and the answer to a part's example is almost
+/// always the last highlighted `<code>` in it. That answer is attached to the first example in the
+/// same article, or the very first example if the article has none of its own, which is what part 2
+/// usually does. It's a heuristic, so it's worth eyeballing the results.
+pub fn extract_examples(html: &str) -> Vec<Example> {
+    let mut examples: Vec<Example> = vec![];
+
+    for (i, article) in ARTICLE.captures_iter(html).enumerate() {
+        let article = &article[1];
+        let part = format!("part{}", i + 1);
+
+        let mut first_in_article = None;
+        for block in PRE_BLOCK.captures_iter(article) {
+            let text = decode_html(&block[1]);
+            let ix = match examples.iter().position(|example| example.text == text) {
+                Some(ix) => ix,
+                None => {
+                    examples.push(Example {
+                        text,
+                        answers: vec![],
+                    });
+                    examples.len() - 1
+                }
+            };
+            first_in_article.get_or_insert(ix);
+        }
+
+        let answer = HIGHLIGHT.captures_iter(article).last().and_then(|caps| {
+            let answer = caps.get(1).or_else(|| caps.get(2))?;
+            Some(decode_html(answer.as_str()))
+        });
+        let target = first_in_article.or((!examples.is_empty()).then_some(0));
+        if let (Some(answer), Some(target)) = (answer, target) {
+            examples[target].answers.push((part, answer));
+        }
+    }
+
+    examples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<main>
+<article class="day-desc"><h2>--- Day 1: Test ---</h2><p>For example:</p>
+<pre><code>3   4
+4   3
+</code></pre>
+<p>Some <code>&lt;tag&gt;</code>, then <code><em>2</em></code> and finally <code><em>11</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>In the <em>same</em> example, the answer is <code><em>31</em></code>.</p>
+</article>
+</main>"#;
+
+    #[test]
+    fn test_extract_examples() {
+        let examples = extract_examples(PAGE);
+        assert_eq!(
+            examples,
+            vec![Example {
+                text: "3   4\n4   3\n".into(),
+                answers: vec![("part1".into(), "11".into()), ("part2".into(), "31".into())],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_decode_html() {
+        assert_eq!(decode_html("a &lt;<em>b</em>&gt; &amp;lt;"), "a <b> &lt;");
+    }
+}
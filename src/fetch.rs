@@ -0,0 +1,280 @@
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use reqwest::header::{HeaderMap, HeaderValue};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_USER_AGENT: &str =
+    "github.com/XavilPergis/advent-of-code-2024 (personal puzzle runner)";
+
+/// Makes requests to the puzzle site, spacing them out so we're not hammering it.
+///
+/// The time of the last request is kept in a file rather than in memory, since most invocations of
+/// the runner only make a single request.
+pub struct Fetcher {
+    client: reqwest::blocking::Client,
+    base_url: String,
+    min_interval: Duration,
+    throttle_file: PathBuf,
+}
+
+impl Fetcher {
+    pub fn new(
+        base_url: &str,
+        session_token: &str,
+        min_interval: Duration,
+        throttle_file: PathBuf,
+    ) -> eyre::Result<Self> {
+        let user_agent =
+            std::env::var("AOC_USER_AGENT").unwrap_or_else(|_| DEFAULT_USER_AGENT.into());
+
+        let mut headers = HeaderMap::new();
+        let mut cookie = HeaderValue::from_str(session_token)
+            .map_err(|_| eyre::eyre!("invalid session token: non-ascii"))?;
+        cookie.set_sensitive(true);
+        headers.insert("Cookie", cookie);
+
+        let client = reqwest::blocking::Client::builder()
+            .user_agent(user_agent)
+            .default_headers(headers)
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').into(),
+            min_interval,
+            throttle_file,
+        })
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    fn throttle(&self) -> eyre::Result<()> {
+        let last = std::fs::read_to_string(&self.throttle_file)
+            .ok()
+            .and_then(|text| text.trim().parse::<u64>().ok())
+            .map(|ms| UNIX_EPOCH + Duration::from_millis(ms));
+
+        if let Some(last) = last {
+            let elapsed = SystemTime::now().duration_since(last).unwrap_or_default();
+            if elapsed < self.min_interval {
+                let wait = self.min_interval - elapsed;
+                status!(
+                    "\x1b[33mwaiting\x1b[0m {} before the next request",
+                    crate::DisplayDuration(wait)
+                );
+                std::thread::sleep(wait);
+            }
+        }
+
+        if let Some(parent) = self.throttle_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        std::fs::write(&self.throttle_file, now.to_string())?;
+        Ok(())
+    }
+
+    fn check_status(url: &str, res: reqwest::blocking::Response) -> eyre::Result<Vec<u8>> {
+        let status = res.status();
+        let body = res.bytes()?;
+        if !status.is_success() {
+            let text = String::from_utf8_lossy(&body);
+            let first_line = text.lines().next().unwrap_or_default();
+            eyre::bail!("request to {url} failed with {status}: {first_line}");
+        }
+        Ok(body.to_vec())
+    }
+
+    /// Requests `path` from the site, failing on anything but a success status.
+    pub fn get(&self, path: &str) -> eyre::Result<Vec<u8>> {
+        let url = self.url(path);
+        self.throttle()?;
+        let res = self.client.get(&url).send()?;
+        Self::check_status(&url, res)
+    }
+
+    /// Posts a form to `path`, failing on anything but a success status.
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> eyre::Result<Vec<u8>> {
+        let url = self.url(path);
+        self.throttle()?;
+        let res = self.client.post(&url).form(form).send()?;
+        Self::check_status(&url, res)
+    }
+}
+
+/// Where and when an input came from, stored next to it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FetchRecord {
+    pub url: String,
+    /// Seconds since the unix epoch.
+    pub fetched_at: u64,
+    pub bytes: usize,
+}
+
+pub fn record_path(file_path: &Path) -> PathBuf {
+    let mut name = file_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".fetch.json");
+    file_path.with_file_name(name)
+}
+
+/// Writes `contents` to `file_path` without ever leaving a half-written file behind, along with a
+/// record of where it came from.
+pub fn save_fetched(file_path: &Path, url: &str, contents: &[u8]) -> eyre::Result<()> {
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut tmp_name = file_path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".partial");
+    let tmp_path = file_path.with_file_name(tmp_name);
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, file_path)?;
+
+    let record = FetchRecord {
+        url: url.into(),
+        fetched_at: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        bytes: contents.len(),
+    };
+    std::fs::write(
+        record_path(file_path),
+        serde_json::to_string_pretty(&record)?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+    };
+
+    /// A request as seen by [`serve`].
+    pub struct Request {
+        pub line: String,
+        pub headers: Vec<(String, String)>,
+        pub body: String,
+    }
+
+    /// Serves one canned `(status, body)` response per incoming request from a local stand-in for
+    /// the puzzle site, and returns its base URL along with the requests it saw.
+    pub fn serve(responses: Vec<(u16, String)>) -> (String, mpsc::Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let mut headers = vec![];
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let Some((key, value)) = header.trim_end().split_once(": ") else {
+                        break;
+                    };
+                    if key.eq_ignore_ascii_case("content-length") {
+                        content_length = value.parse().unwrap();
+                    }
+                    headers.push((key.to_ascii_lowercase(), value.into()));
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).unwrap();
+
+                tx.send(Request {
+                    line: line.trim_end().into(),
+                    headers,
+                    body: String::from_utf8(request_body).unwrap(),
+                })
+                .unwrap();
+
+                let mut stream = stream;
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+
+        (base_url, rx)
+    }
+
+    pub fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc2024-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_fetch_saves_only_successes() {
+        let dir = temp_dir("fetch");
+        let (base_url, requests) = serve(vec![
+            (404, "Please don't repeatedly request this endpoint".into()),
+            (200, "1 2 3\n".into()),
+        ]);
+        let fetcher = Fetcher::new(
+            &base_url,
+            "session=abc",
+            Duration::ZERO,
+            dir.join(".last-request"),
+        )
+        .unwrap();
+
+        assert!(fetcher.get("/2024/day/1/input").is_err());
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "GET /2024/day/1/input HTTP/1.1");
+        assert!(request
+            .headers
+            .contains(&("cookie".into(), "session=abc".into())));
+        assert!(request
+            .headers
+            .contains(&("user-agent".into(), DEFAULT_USER_AGENT.into())));
+
+        let body = fetcher.get("/2024/day/1/input").unwrap();
+        let path = dir.join("day1.txt");
+        save_fetched(&path, &fetcher.url("/2024/day/1/input"), &body).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "1 2 3\n");
+
+        let record: FetchRecord =
+            serde_json::from_str(&std::fs::read_to_string(record_path(&path)).unwrap()).unwrap();
+        assert_eq!(record.url, format!("{base_url}/2024/day/1/input"));
+        assert_eq!(record.bytes, 6);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_throttle_waits() {
+        let dir = temp_dir("throttle");
+        let fetcher = Fetcher::new(
+            "http://unused",
+            "session=abc",
+            Duration::from_millis(200),
+            dir.join(".last-request"),
+        )
+        .unwrap();
+
+        fetcher.throttle().unwrap();
+        let start = std::time::Instant::now();
+        fetcher.throttle().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(150));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+};
+
+use serde::Deserialize;
+
+use crate::{Puzzle, HOME_YEAR};
+
+/// Where input sets live on disk, and where a day's files are within a set.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct InputLayout {
+    pub dir: PathBuf,
+    /// The path of a day's files within a set, minus the extension. `{year}` and `{day}` are
+    /// filled in.
+    pub path: String,
+}
+
+impl Default for InputLayout {
+    fn default() -> Self {
+        Self {
+            dir: "inputs".into(),
+            path: DEFAULT_PATH.into(),
+        }
+    }
+}
+
+const DEFAULT_PATH: &str = "{year}/day{day}";
+/// Where a day's files were before inputs were split up by year, back when every puzzle was from
+/// the home year.
+const LEGACY_PATH: &str = "day{day}";
+
+fn fill_path(path: &str, puzzle: Puzzle) -> String {
+    path.replace("{year}", &puzzle.year.to_string())
+        .replace("{day}", &puzzle.day.to_string())
+}
+
+/// A collection of puzzle inputs that all belong to the same account, along with the answers that
+/// are known to be correct for them.
+///
+/// The unnamed set lives directly in `inputs/`, and named sets live in `inputs/<name>/`. Within a
+/// set, each event has its own directory by default, so the real input for a day is
+/// `<year>/dayN.txt`. Examples taken from the puzzle pages live alongside the real inputs, as
+/// `dayN.exampleK.txt`.
+///
+/// Sets from before that keep the home year's days directly in the set as `dayN.txt`. With the
+/// default layout, a day that only has an input there keeps all of its files there, so existing
+/// answers and submissions are still found.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputSet {
+    layout: InputLayout,
+    name: Option<String>,
+    example: Option<u32>,
+}
+
+impl InputSet {
+    pub fn default_set(layout: &InputLayout) -> Self {
+        Self {
+            layout: layout.clone(),
+            name: None,
+            example: None,
+        }
+    }
+
+    pub fn named(layout: &InputLayout, name: &str) -> Self {
+        Self {
+            layout: layout.clone(),
+            name: Some(name.into()),
+            example: None,
+        }
+    }
+
+    /// The same set, but pointing at its `k`th example rather than the real input.
+    pub fn with_example(self, example: Option<u32>) -> Self {
+        Self { example, ..self }
+    }
+
+    pub fn is_example(&self) -> bool {
+        self.example.is_some()
+    }
+
+    /// Every input set found on disk, the unnamed set first.
+    pub fn discover(layout: &InputLayout) -> eyre::Result<Vec<InputSet>> {
+        let mut sets = vec![];
+        if std::fs::exists(&layout.dir)? {
+            for entry in std::fs::read_dir(&layout.dir)? {
+                let entry = entry?;
+                if !entry.file_type()?.is_dir() {
+                    continue;
+                }
+                // year directories belong to the unnamed set.
+                if let Some(name) = entry.file_name().to_str() {
+                    if !name.bytes().all(|ch| ch.is_ascii_digit()) {
+                        sets.push(InputSet::named(layout, name));
+                    }
+                }
+            }
+        }
+        sets.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        sets.insert(0, InputSet::default_set(layout));
+        Ok(sets)
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name.is_none()
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        match &self.name {
+            Some(name) => self.layout.dir.join(name),
+            None => self.layout.dir.clone(),
+        }
+    }
+
+    fn file_path(&self, puzzle: Puzzle, extension: &str) -> PathBuf {
+        let stem = fill_path(&self.layout.path, puzzle);
+        let stem = match self.layout.path == DEFAULT_PATH && puzzle.year == HOME_YEAR {
+            true => self.legacy_stem(puzzle).unwrap_or(stem),
+            false => stem,
+        };
+        let stem = match self.example {
+            Some(k) => format!("{stem}.example{k}"),
+            None => stem,
+        };
+        self.dir().join(format!("{stem}.{extension}"))
+    }
+
+    /// The flat layout's stem, if that's the only place this day's input is.
+    fn legacy_stem(&self, puzzle: Puzzle) -> Option<String> {
+        let [stem, legacy] = [DEFAULT_PATH, LEGACY_PATH].map(|path| fill_path(path, puzzle));
+        let dir = self.dir();
+        if dir.join(format!("{stem}.txt")).exists() || !dir.join(format!("{legacy}.txt")).exists() {
+            return None;
+        }
+
+        static WARNED: AtomicBool = AtomicBool::new(false);
+        if !WARNED.swap(true, Ordering::Relaxed) {
+            eprintln!(
+                "\x1b[33mwarning\x1b[0m: found {} in the old flat layout, move the home year's \
+                 files into {} to use the per-year layout",
+                dir.join(format!("{legacy}.txt")).display(),
+                dir.join(HOME_YEAR.to_string()).display(),
+            );
+        }
+        Some(legacy)
+    }
+
+    pub fn input_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.file_path(puzzle, "txt")
+    }
+
+    pub fn answers_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.file_path(puzzle, "answers")
+    }
+
+    pub fn submissions_path(&self, puzzle: Puzzle) -> PathBuf {
+        self.file_path(puzzle, "submissions")
+    }
+
+    pub fn load_answers(&self, puzzle: Puzzle) -> eyre::Result<Answers> {
+        Answers::load(self.answers_path(puzzle))
+    }
+}
+
+impl std::fmt::Display for InputSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{name}")?,
+            None => write!(f, "default")?,
+        }
+        match self.example {
+            Some(k) => write!(f, " example {k}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The expected answers for a single day of an input set, keyed by part.
+///
+/// Stored as one `part: answer` pair per line.
+#[derive(Clone, Debug)]
+pub struct Answers {
+    path: PathBuf,
+    parts: BTreeMap<String, String>,
+}
+
+impl Answers {
+    pub fn load(path: PathBuf) -> eyre::Result<Self> {
+        let mut parts = BTreeMap::new();
+        if std::fs::exists(&path)? {
+            let text = std::fs::read_to_string(&path)?;
+            for (i, line) in text.lines().enumerate() {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let Some((part, answer)) = line.split_once(':') else {
+                    eyre::bail!("{}:{}: expected `part: answer`", path.display(), i + 1);
+                };
+                parts.insert(part.trim().into(), answer.trim().into());
+            }
+        }
+        Ok(Self { path, parts })
+    }
+
+    pub fn save(&self) -> eyre::Result<()> {
+        let mut text = String::new();
+        for (part, answer) in &self.parts {
+            writeln!(text, "{part}: {answer}")?;
+        }
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, text)?;
+        Ok(())
+    }
+
+    pub fn get(&self, part: &str) -> Option<&str> {
+        self.parts.get(part).map(|answer| &**answer)
+    }
+
+    pub fn insert(&mut self, part: &str, answer: &str) {
+        self.parts.insert(part.into(), answer.into());
+    }
+}
+
+/// The text of an input, either read into memory or mapped straight from its file.
+pub enum InputData {
+    Owned(String),
+    /// Mapped pages are backed by the file rather than counted as memory the runner allocated, so
+    /// huge generated inputs don't need to be copied before a variant can start on them.
+    Mapped(memmap2::Mmap),
+    Stdin(&'static str),
+}
+
+impl InputData {
+    pub fn read(path: &Path, mmap: bool) -> eyre::Result<Self> {
+        if !mmap {
+            return Ok(Self::Owned(std::fs::read_to_string(path)?));
+        }
+
+        let file = std::fs::File::open(path)?;
+        // mapping an empty file fails on some platforms, and there's nothing to gain anyways.
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::Owned(String::new()));
+        }
+        // SAFETY: the map is only sound as long as nobody modifies the file while we're running,
+        // which is a reasonable thing to expect of puzzle inputs.
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        if let Err(err) = std::str::from_utf8(&map) {
+            eyre::bail!("{} is not valid UTF-8: {err}", path.display());
+        }
+        Ok(Self::Mapped(map))
+    }
+
+    /// Reads all of stdin. It can only be read once, so later calls get the same text back.
+    pub fn stdin() -> eyre::Result<Self> {
+        static STDIN: OnceLock<String> = OnceLock::new();
+        if let Some(text) = STDIN.get() {
+            return Ok(Self::Stdin(text));
+        }
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text)?;
+        Ok(Self::Stdin(STDIN.get_or_init(|| text)))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            InputData::Owned(text) => text,
+            // SAFETY: checked to be UTF-8 when the file was mapped.
+            InputData::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+            InputData::Stdin(text) => text,
+        }
+    }
+}
+
+/// The part a variant solves, so that eg. `part1_bitset` is checked against the answer to `part1`.
+pub fn part_of_variant(variant: &str) -> Option<&str> {
+    let part = variant.split('_').next()?;
+    match part.strip_prefix("part") {
+        Some(n) if !n.is_empty() && n.bytes().all(|ch| ch.is_ascii_digit()) => Some(part),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect {
+        expected: String,
+    },
+    /// There's no stored answer to check against.
+    Unknown,
+    /// The variant didn't produce an answer at all.
+    Failed,
+}
+
+pub fn verify(answers: &Answers, variant: &str, answer: Option<&str>) -> Verdict {
+    let Some(answer) = answer else {
+        return Verdict::Failed;
+    };
+    match part_of_variant(variant).and_then(|part| answers.get(part)) {
+        Some(expected) if expected == answer => Verdict::Correct,
+        Some(expected) => Verdict::Incorrect {
+            expected: expected.into(),
+        },
+        None => Verdict::Unknown,
+    }
+}
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::Incorrect { .. } => "incorrect",
+            Verdict::Unknown => "unverified",
+            Verdict::Failed => "failed",
+        }
+    }
+}
+
+impl std::fmt::Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "\x1b[32mcorrect\x1b[0m"),
+            Verdict::Incorrect { expected } => {
+                write!(f, "\x1b[31mincorrect\x1b[0m (expected {expected})")
+            }
+            Verdict::Unknown => write!(f, "\x1b[33munverified\x1b[0m"),
+            Verdict::Failed => write!(f, "\x1b[31mfailed\x1b[0m"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part_of_variant() {
+        assert_eq!(part_of_variant("part1"), Some("part1"));
+        assert_eq!(part_of_variant("part2_no_recursion_flat"), Some("part2"));
+        assert_eq!(part_of_variant("generate_lut"), None);
+        assert_eq!(part_of_variant("part_x"), None);
+    }
+
+    #[test]
+    fn test_paths() {
+        let puzzle = Puzzle { year: 2023, day: 7 };
+        let layout = InputLayout::default();
+        assert_eq!(
+            InputSet::default_set(&layout).input_path(puzzle),
+            PathBuf::from("inputs/2023/day7.txt")
+        );
+        assert_eq!(
+            InputSet::named(&layout, "alt")
+                .with_example(Some(2))
+                .answers_path(puzzle),
+            PathBuf::from("inputs/alt/2023/day7.example2.answers")
+        );
+
+        let flat = InputLayout {
+            dir: "puzzles".into(),
+            path: "day{day}-{year}".into(),
+        };
+        assert_eq!(
+            InputSet::default_set(&flat).input_path(puzzle),
+            PathBuf::from("puzzles/day7-2023.txt")
+        );
+    }
+
+    #[test]
+    fn test_legacy_layout() {
+        let dir = std::env::temp_dir().join(format!("aoc2024-legacy-{}", std::process::id()));
+        let layout = InputLayout {
+            dir: dir.clone(),
+            ..InputLayout::default()
+        };
+        let set = InputSet::named(&layout, "alt");
+        let home = Puzzle {
+            year: HOME_YEAR,
+            day: 3,
+        };
+        std::fs::create_dir_all(set.dir()).unwrap();
+        std::fs::write(set.dir().join("day3.txt"), "input\n").unwrap();
+        std::fs::write(set.dir().join("day3.answers"), "part1: 42\n").unwrap();
+
+        // a day that's only in the flat layout keeps all of its files there.
+        assert_eq!(set.input_path(home), dir.join("alt/day3.txt"));
+        assert_eq!(set.submissions_path(home), dir.join("alt/day3.submissions"));
+        assert_eq!(set.load_answers(home).unwrap().get("part1"), Some("42"));
+        assert_eq!(
+            set.clone().with_example(Some(1)).input_path(home),
+            dir.join("alt/day3.example1.txt")
+        );
+
+        // other years were never stored flat.
+        let other = Puzzle { year: 2023, day: 3 };
+        assert_eq!(set.input_path(other), dir.join("alt/2023/day3.txt"));
+
+        // once the day exists in the per-year layout, that wins.
+        std::fs::create_dir_all(set.dir().join(HOME_YEAR.to_string())).unwrap();
+        let moved = dir.join(format!("alt/{HOME_YEAR}/day3.txt"));
+        std::fs::write(&moved, "input\n").unwrap();
+        assert_eq!(set.input_path(home), moved);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_mapped_input() {
+        let path = std::env::temp_dir().join(format!("aoc2024-mmap-{}.txt", std::process::id()));
+        std::fs::write(&path, "1 2 3\n").unwrap();
+        assert_eq!(InputData::read(&path, true).unwrap().as_str(), "1 2 3\n");
+        assert_eq!(InputData::read(&path, false).unwrap().as_str(), "1 2 3\n");
+
+        std::fs::write(&path, [0xff, 0xfe]).unwrap();
+        assert!(InputData::read(&path, true).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut answers = Answers {
+            path: PathBuf::new(),
+            parts: BTreeMap::new(),
+        };
+        answers.insert("part1", "42");
+        assert_eq!(verify(&answers, "part1_fast", Some("42")), Verdict::Correct);
+        assert_eq!(
+            verify(&answers, "part1", Some("41")),
+            Verdict::Incorrect {
+                expected: "42".into()
+            }
+        );
+        assert_eq!(verify(&answers, "part2", Some("1")), Verdict::Unknown);
+        assert_eq!(verify(&answers, "part1", None), Verdict::Failed);
+    }
+}
@@ -1,40 +1,185 @@
-#![feature(portable_simd)]
+#![cfg_attr(feature = "nightly-simd", feature(portable_simd))]
 
 use std::{
     collections::HashMap,
     fmt::Display,
+    io::Write,
     path::PathBuf,
-    time::{Duration, Instant},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
-use reqwest::header::{HeaderMap, HeaderValue};
+use config::{OutputFormat, ProjectConfig};
+use environment::RunEnvironment;
+use fetch::Fetcher;
+use inputs::{InputData, InputSet, Verdict};
+use scratch::{ScratchBuffer, ScratchSlice};
+use serde::Serialize;
 use structopt::StructOpt;
+use submit::{Outcome, SubmissionLog};
+use visualize::Visualizer;
+
+/// Set when stdout is reserved for JSON lines, so everything else has to go to stderr.
+static STDOUT_IS_JSON: AtomicBool = AtomicBool::new(false);
+
+/// Like `println!`, for progress and answers that would get in the way of `--output json`.
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::STDOUT_IS_JSON.load(std::sync::atomic::Ordering::Relaxed) {
+            eprintln!($($arg)*)
+        } else {
+            println!($($arg)*)
+        }
+    };
+}
 
 #[derive(Clone, Debug, StructOpt)]
 struct RunConfig {
+    #[structopt(long, default_value = "aoc.toml")]
+    /// The project config to take defaults from. It's fine for it not to exist.
+    pub config: PathBuf,
+    #[structopt(skip)]
+    pub project: ProjectConfig,
     #[structopt(long, short)]
-    /// The file path of the input to use.
+    /// The file path of the input to use, or `-` to read it from stdin.
     pub input: Option<String>,
-    #[structopt(long, short = "s", default_value = "1")]
-    pub sample_count: usize,
-    #[structopt(long, short, default_value = "60")]
-    pub rerun_time_limit_s: f64,
+    #[structopt(long)]
+    /// Memory-map input files instead of reading them into memory.
+    pub mmap: bool,
+    #[structopt(long, conflicts_with = "input")]
+    /// The named input set to use, from `inputs/<set>/`.
+    pub set: Option<String>,
+    #[structopt(long, conflicts_with_all = &["input", "set"])]
+    /// Run and verify against every input set.
+    pub all_sets: bool,
+    #[structopt(long, short = "e", conflicts_with = "input")]
+    /// Run against an example saved by `fetch-examples` instead of the real input.
+    pub example: Option<u32>,
+    #[structopt(long)]
+    /// Check that the input has the shape the day expects before running anything.
+    pub validate: bool,
+    #[structopt(long, env = "AOC_YEAR")]
+    /// The event to use for variants and days that don't name one.
+    pub year: Option<u32>,
+    #[structopt(long, env = "AOC_BASE_URL", default_value = fetch::DEFAULT_BASE_URL)]
+    /// The site to fetch inputs from.
+    pub base_url: String,
+    #[structopt(long, default_value = "5")]
+    /// The minimum time to leave between requests to the site, in seconds.
+    pub fetch_interval_s: f64,
+    #[structopt(long)]
+    /// Append each result, along with the environment it was measured in, to this file as a line
+    /// of JSON.
+    pub export: Option<PathBuf>,
+    #[structopt(long)]
+    /// Store answers as the expected answer for input sets that don't have one yet.
+    pub record_answers: bool,
+    #[structopt(long)]
+    /// How to print results, either `pretty` or `json`.
+    pub output: Option<OutputFormat>,
+    #[structopt(long, short = "s")]
+    /// How many times to run each variant. Defaults to 1.
+    pub sample_count: Option<usize>,
+    #[structopt(long, short)]
+    /// Stop taking samples after this many seconds. Defaults to 60.
+    pub rerun_time_limit_s: Option<f64>,
+    #[structopt(long)]
+    /// Write frames from variants that draw them into this directory. Needs the `visualize`
+    /// feature.
+    pub visualize: Option<PathBuf>,
+    #[cfg(feature = "visualize")]
+    #[structopt(long, default_value = "png")]
+    /// The format of visualized frames, one of `png`, `ppm` or `gif`.
+    pub frame_format: visualize::FrameFormat,
+    #[cfg(feature = "visualize")]
+    #[structopt(long, default_value = "4")]
+    /// How many pixels across each cell of a visualized grid is.
+    pub frame_scale: usize,
+    #[cfg(feature = "visualize")]
+    #[structopt(long, default_value = "1")]
+    /// Only keep every Nth visualized frame.
+    pub frame_stride: usize,
+    #[structopt(long, default_value = "64")]
+    /// The alignment of the input scratch buffer, in bytes.
+    pub scratch_align: usize,
+    #[structopt(long, default_value = "64")]
+    /// The minimum number of zeroed bytes after the input in the scratch buffer, never less than 64.
+    pub scratch_padding: usize,
     #[structopt(subcommand)]
     pub subcommand: RunCommand,
 }
 
 #[derive(Clone, Debug, StructOpt)]
 enum RunCommand {
-    Run { variant: Option<String> },
-    Compare { variant1: String, variant2: String },
+    Run {
+        variant: Option<String>,
+    },
+    Compare {
+        variant1: String,
+        variant2: String,
+    },
     List,
-    Fetch { day: u32 },
+    Fetch {
+        day: u32,
+    },
+    /// Saves the examples from a day's puzzle page, along with their answers.
+    FetchExamples {
+        day: u32,
+    },
+    /// Creates `src/days/dayN.rs` with stubs for both parts.
+    New {
+        day: u32,
+    },
+    /// Runs a variant on the real input and submits its answer. Defaults to the `partN` variant.
+    Submit {
+        day: u32,
+        part: u32,
+        variant: Option<String>,
+    },
+}
+
+// command line flags win over the day's overrides in the project config, which win over its
+// defaults.
+impl RunConfig {
+    fn year(&self) -> u32 {
+        self.year.or(self.project.year).unwrap_or(HOME_YEAR)
+    }
+
+    fn day_config(&self, puzzle: Puzzle) -> Option<&config::DayConfig> {
+        self.project.day(puzzle, self.year())
+    }
+
+    fn sample_count(&self, puzzle: Puzzle) -> usize {
+        self.sample_count
+            .or_else(|| self.day_config(puzzle)?.sample_count)
+            .or(self.project.sample_count)
+            .unwrap_or(1)
+    }
+
+    fn rerun_time_limit(&self, puzzle: Puzzle) -> Duration {
+        let seconds = self
+            .rerun_time_limit_s
+            .or_else(|| self.day_config(puzzle)?.rerun_time_limit_s)
+            .or(self.project.rerun_time_limit_s)
+            .unwrap_or(60.0);
+        Duration::from_secs_f64(seconds)
+    }
+
+    fn output(&self) -> OutputFormat {
+        self.output.or(self.project.output).unwrap_or_default()
+    }
+
+    fn default_set(&self) -> InputSet {
+        InputSet::default_set(&self.project.inputs)
+    }
 }
 
 pub struct RunContext<'a> {
     pub input: &'a str,
-    pub input_scratch: &'a mut [u8],
+    pub input_scratch: ScratchSlice<'a>,
+    pub visualizer: Visualizer,
     write_output: bool,
+    answer: Option<String>,
     begin_timestamp: Option<Instant>,
     parsed_timestamp: Option<Instant>,
     complete_timestamp: Option<Instant>,
@@ -46,55 +191,195 @@ impl RunContext<'_> {
     }
 }
 
+/// Days that are directly in `days/` rather than in a `yearYYYY` module are from this event.
+pub const HOME_YEAR: u32 = 2024;
+
+/// A single day of a single event, written like `2024.d7`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Puzzle {
+    pub year: u32,
+    pub day: u32,
+}
+
+impl Puzzle {
+    /// Parses `d7`, or `2023.d7` for a day from another event than `default_year`.
+    pub fn parse(text: &str, default_year: u32) -> eyre::Result<Self> {
+        let (year, day) = match text.split_once('.') {
+            Some((year, day)) => {
+                let Ok(year) = year.parse::<u32>() else {
+                    eyre::bail!("invalid year '{year}'");
+                };
+                (year, day)
+            }
+            None => (default_year, text),
+        };
+        let Ok(day) = day.trim_start_matches('d').parse::<u32>() else {
+            eyre::bail!("invalid day '{day}'");
+        };
+        Ok(Self { year, day })
+    }
+}
+
+impl std::fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.d{}", self.year, self.day)
+    }
+}
+
+/// A variant registered with `#[variant]`, collected from every day module at link time.
+pub struct VariantEntry {
+    pub module: &'static str,
+    pub year: Option<u32>,
+    pub day: Option<u32>,
+    pub name: &'static str,
+    pub runner: Runner,
+}
+
+pub type Runner = fn(&mut RunContext);
+
+#[linkme::distributed_slice]
+pub static VARIANTS: [VariantEntry];
+
+/// An input validator registered with `#[validator]`.
+pub struct ValidatorEntry {
+    pub module: &'static str,
+    pub year: Option<u32>,
+    pub day: Option<u32>,
+    pub validate: Validate,
+}
+
+pub type Validate = fn(&str) -> validate::ValidateResult;
+
+#[linkme::distributed_slice]
+pub static VALIDATORS: [ValidatorEntry];
+
+#[derive(Default)]
 pub struct RunnerRepository {
-    current_day: u32,
-    days: HashMap<u32, HashMap<String, Box<dyn Fn(&mut RunContext)>>>,
+    puzzles: HashMap<Puzzle, HashMap<String, Runner>>,
+    validators: HashMap<Puzzle, Validate>,
 }
 
 impl RunnerRepository {
     pub fn new() -> Self {
-        Self {
-            current_day: 0,
-            days: Default::default(),
+        Self::default()
+    }
+
+    /// Builds a repository out of every variant registered with `#[variant]`.
+    pub fn collect() -> eyre::Result<Self> {
+        let mut repo = Self::new();
+        for entry in VARIANTS {
+            let puzzle = puzzle_of_entry(entry.module, entry.year, entry.day).ok_or_else(|| {
+                eyre::eyre!(
+                    "could not infer the day of {}::{}, specify it with #[variant(day = N)]",
+                    entry.module,
+                    entry.name
+                )
+            })?;
+            repo.add_variant(puzzle, entry.name, entry.runner)?;
+        }
+        for entry in VALIDATORS {
+            let puzzle = puzzle_of_entry(entry.module, entry.year, entry.day).ok_or_else(|| {
+                eyre::eyre!(
+                    "could not infer the day of the validator in {}, specify it with #[validator(day = N)]",
+                    entry.module
+                )
+            })?;
+            repo.add_validator(puzzle, entry.validate)?;
         }
+        Ok(repo)
     }
 }
 
 impl RunnerRepository {
-    pub fn merge_day(&mut self, day: u32, register: fn(&mut RunnerRepository)) {
-        self.current_day = day;
-        register(self)
+    pub fn add_variant(&mut self, puzzle: Puzzle, name: &str, runner: Runner) -> eyre::Result<()> {
+        let variants = self.puzzles.entry(puzzle).or_default();
+        if variants.insert(name.into(), runner).is_some() {
+            eyre::bail!("{puzzle}.{name} was registered more than once");
+        }
+        Ok(())
     }
 
-    pub fn add_variant<T, F>(&mut self, name: &'static str, runner: F)
-    where
-        F: Fn(&mut RunContext) -> eyre::Result<T> + 'static,
-        T: Display,
-    {
-        let variants = self
-            .days
-            .entry(self.current_day)
-            .or_insert_with(|| Default::default());
-        variants.insert(
-            name.into(),
-            Box::new(move |ctx| {
-                ctx.begin_timestamp = Some(Instant::now());
-                let res = runner(ctx);
-                ctx.complete_timestamp = Some(Instant::now());
-                if ctx.write_output {
-                    match res {
-                        Ok(value) => println!("{value}"),
-                        Err(err) => println!("\x1b[31merror\x1b[0m:\n{err:?}"),
-                    }
-                }
-            }),
-        );
+    pub fn add_validator(&mut self, puzzle: Puzzle, validate: Validate) -> eyre::Result<()> {
+        if self.validators.insert(puzzle, validate).is_some() {
+            eyre::bail!("{puzzle} has more than one input validator");
+        }
+        Ok(())
+    }
+}
+
+fn number_from_module(module: &str, prefix: &str) -> Option<u32> {
+    module
+        .rsplit("::")
+        .find_map(|segment| segment.strip_prefix(prefix)?.parse().ok())
+}
+
+fn puzzle_of_entry(module: &str, year: Option<u32>, day: Option<u32>) -> Option<Puzzle> {
+    Some(Puzzle {
+        year: year
+            .or_else(|| number_from_module(module, "year"))
+            .unwrap_or(HOME_YEAR),
+        day: day.or_else(|| number_from_module(module, "day"))?,
+    })
+}
+
+/// Times a single variant invocation and prints its answer. This is what `#[variant]` wraps solver
+/// functions with.
+pub fn report_variant<T, F>(ctx: &mut RunContext, runner: F)
+where
+    F: FnOnce(&mut RunContext) -> eyre::Result<T>,
+    T: Display,
+{
+    ctx.begin_timestamp = Some(Instant::now());
+    let res = runner(ctx);
+    ctx.complete_timestamp = Some(Instant::now());
+    if ctx.write_output {
+        match res {
+            Ok(value) => {
+                let answer = value.to_string();
+                status!("{answer}");
+                ctx.answer = Some(answer);
+            }
+            Err(err) => status!("\x1b[31merror\x1b[0m:\n{err:?}"),
+        }
     }
 }
 
+/// Runs a solver on `input` directly, without any of the timing or printing the runner does.
+#[cfg(test)]
+pub fn run_on<T, F>(input: &str, runner: F) -> eyre::Result<T>
+where
+    F: FnOnce(&mut RunContext) -> eyre::Result<T>,
+{
+    let mut scratch = ScratchBuffer::new(input.len(), 64, scratch::MIN_PADDING)?;
+    let mut ctx = RunContext {
+        input,
+        input_scratch: scratch.slice(),
+        visualizer: Visualizer::default(),
+        write_output: false,
+        answer: None,
+        begin_timestamp: None,
+        parsed_timestamp: None,
+        complete_timestamp: None,
+    };
+    ctx.input_scratch.restore(input.as_bytes());
+    runner(&mut ctx)
+}
+
+pub mod bitgrid;
 pub mod bitset;
+mod config;
+mod environment;
+mod examples;
+mod fetch;
+mod inputs;
+pub mod roaring;
+pub mod scratch;
+mod submit;
+pub mod validate;
+pub mod visualize;
 
 // i fucking hate that macros are sensitive to declaration order. why is it like this.
+#[allow(unused_macros, reason = "unused when day 18 isn't compiled in")]
 macro_rules! as_display {
     ($($arg:tt)*) => {{
         $crate::WrapAsDisplay(move |f: &mut std::fmt::Formatter<'_>| write!(f, $($arg)*))
@@ -104,90 +389,262 @@ macro_rules! as_display {
 mod days;
 
 pub mod prelude {
+    pub use crate::bitset::Bits;
+    #[cfg(test)]
+    pub use crate::run_on;
+    pub use crate::validate::{ValidateResult, Validator};
+    pub use crate::visualize::{Frame, Rgb};
     pub use crate::{RunContext, RunnerRepository};
+    pub use aoc2024_macros::{validator, variant};
     pub use std::fmt::Display;
 }
 
-fn fetch_day(day: u32) -> eyre::Result<()> {
-    let file_path = format!("inputs/day{day}.txt");
+fn make_fetcher(config: &RunConfig) -> eyre::Result<Fetcher> {
+    let Ok(session_token) = std::env::var("AOC_TOKEN") else {
+        eyre::bail!("AOC_TOKEN env var not specified, could not fetch missing input.");
+    };
+    Fetcher::new(
+        &config.base_url,
+        &session_token,
+        Duration::from_secs_f64(config.fetch_interval_s),
+        config.project.inputs.dir.join(".last-request"),
+    )
+}
+
+fn fetch_day(config: &RunConfig, puzzle: Puzzle) -> eyre::Result<()> {
+    let file_path = config.default_set().input_path(puzzle);
 
     if !std::fs::exists(&file_path)? {
-        let Ok(session_token) = std::env::var("AOC_TOKEN") else {
-            eyre::bail!("AOC_TOKEN env var not specified, could not fetch missing input.");
-        };
+        let fetcher = make_fetcher(config)?;
+        let Puzzle { year, day } = puzzle;
+        let path = format!("/{year}/day/{day}/input");
 
-        let file_path = PathBuf::from(&file_path);
-        let Some(parent_dir) = file_path.parent() else {
-            eyre::bail!("not a valid file path.");
-        };
+        status!("\x1b[31mfetching missing input for {puzzle}\x1b[0m");
+        let input = fetcher.get(&path)?;
+        fetch::save_fetched(&file_path, &fetcher.url(&path), &input)?;
+    }
+
+    Ok(())
+}
+
+/// Writes a new day module from `templates/day.rs`. `build.rs` picks it up on the next build, and
+/// `#[variant]` takes care of the rest.
+fn new_day(repo: &RunnerRepository, puzzle: Puzzle) -> eyre::Result<()> {
+    // build.rs only compiles a day in with its `dayN` feature, and those only go up to 25.
+    if !(1..=25).contains(&puzzle.day) {
+        eyre::bail!("{puzzle} is not a puzzle, days go from 1 to 25");
+    }
+    if repo.puzzles.contains_key(&puzzle) {
+        eyre::bail!("{puzzle} already has variants");
+    }
+
+    let mut dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/days");
+    if puzzle.year != HOME_YEAR {
+        dir.push(format!("year{}", puzzle.year));
+    }
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("day{}.rs", puzzle.day));
+
+    let source = include_str!("../templates/day.rs").replace("{day}", &puzzle.day.to_string());
+    // `create_new` so that an existing day is never clobbered, even if it has no variants yet.
+    let mut file = match std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+    {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+            eyre::bail!("{} already exists", path.display());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    file.write_all(source.as_bytes())?;
+
+    println!("\x1b[32mcreated\x1b[0m {}", path.display());
+    Ok(())
+}
+
+fn make_visualizer(config: &RunConfig, puzzle: Puzzle, variant: &str) -> eyre::Result<Visualizer> {
+    let Some(dir) = &config.visualize else {
+        return Ok(Visualizer::default());
+    };
+
+    #[cfg(feature = "visualize")]
+    {
+        let writer = visualize::FrameWriter::new(
+            &dir.join(format!("{puzzle}.{variant}")),
+            config.frame_format,
+            config.frame_scale,
+            config.frame_stride,
+        )?;
+        Ok(Visualizer::new(writer))
+    }
+    #[cfg(not(feature = "visualize"))]
+    {
+        let _ = (dir, puzzle, variant);
+        eyre::bail!("--visualize needs the runner to be built with the `visualize` feature")
+    }
+}
+
+struct VariantRun {
+    /// Where the input came from.
+    source: String,
+    samples: Vec<Sample>,
+    /// The answer printed by the first sample, if it succeeded.
+    answer: Option<String>,
+}
 
-        let year = 2024;
+fn fetch_examples(config: &RunConfig, puzzle: Puzzle) -> eyre::Result<()> {
+    let fetcher = make_fetcher(config)?;
+    let Puzzle { year, day } = puzzle;
+    let path = format!("/{year}/day/{day}");
 
-        let mut headers = HeaderMap::new();
-        let mut cookie = HeaderValue::from_str(&session_token)
-            .map_err(|_| eyre::eyre!("invalid session token: non-ascii"))?;
-        cookie.set_sensitive(true);
-        headers.insert("Cookie", cookie);
+    println!("\x1b[32mfetching\x1b[0m examples for {puzzle}");
+    let page = String::from_utf8(fetcher.get(&path)?)?;
+    let examples = examples::extract_examples(&page);
+    if examples.is_empty() {
+        eyre::bail!("no examples found on the page for {puzzle}");
+    }
 
-        println!("\x1b[31mfetching missing input for day {day}\x1b[0m");
+    for (i, example) in examples.iter().enumerate() {
+        let set = config.default_set().with_example(Some(i as u32 + 1));
+        let file_path = set.input_path(puzzle);
+        fetch::save_fetched(&file_path, &fetcher.url(&path), example.text.as_bytes())?;
 
-        let res = reqwest::blocking::Client::builder()
-            .default_headers(headers)
-            .build()?
-            .get(format!("https://adventofcode.com/{year}/day/{day}/input"))
-            .send()?;
+        let mut answers = set.load_answers(puzzle)?;
+        for (part, answer) in &example.answers {
+            answers.insert(part, answer);
+        }
+        answers.save()?;
 
-        std::fs::create_dir_all(parent_dir)?;
-        std::fs::write(&file_path, &res.bytes()?[..])?;
+        let answers = example
+            .answers
+            .iter()
+            .map(|(part, answer)| format!("{part} = {answer}"))
+            .collect::<Vec<_>>();
+        println!(
+            "\x1b[32msaved\x1b[0m {} [{}]",
+            file_path.display(),
+            answers.join(", ")
+        );
     }
 
     Ok(())
 }
 
-fn run_variant(
+fn submit_variant(
     repo: &RunnerRepository,
     config: &RunConfig,
-    day: u32,
-    variant: &str,
-) -> eyre::Result<Vec<Sample>> {
-    if !repo.days.contains_key(&day) {
-        eyre::bail!("day {day} does not exist");
+    puzzle: Puzzle,
+    part: u32,
+    variant: Option<&str>,
+) -> eyre::Result<()> {
+    if config.input.is_some() || config.set.is_some() || config.example.is_some() {
+        eyre::bail!("answers can only be submitted for the default input set's real input");
+    }
+
+    let part_name = format!("part{part}");
+    let variant = variant.map_or_else(|| part_name.clone(), Into::into);
+    // otherwise another part's answer would be sent, and stored as this part's if it was right.
+    if inputs::part_of_variant(&variant) != Some(&*part_name) {
+        eyre::bail!("{puzzle}.{variant} is not a variant of {part_name}");
+    }
+    let set = config.default_set();
+    let run = run_variant(repo, config, puzzle, &variant, &set)?;
+    let Some(answer) = run.answer else {
+        eyre::bail!("{puzzle}.{variant} did not produce an answer");
+    };
+
+    let fetcher = make_fetcher(config)?;
+    let mut log = SubmissionLog::load(set.submissions_path(puzzle))?;
+    let Puzzle { year, day } = puzzle;
+    let outcome = submit::submit_answer(&fetcher, &mut log, year, day, part, &answer)?;
+    println!("[\x1b[32msubmitted\x1b[0m {answer}] [{outcome}]");
+
+    if outcome == Outcome::Correct {
+        let mut answers = set.load_answers(puzzle)?;
+        answers.insert(&part_name, &answer);
+        answers.save()?;
     }
 
-    let variants = &repo.days[&day];
+    Ok(())
+}
+
+fn run_variant(
+    repo: &RunnerRepository,
+    config: &RunConfig,
+    puzzle: Puzzle,
+    variant: &str,
+    set: &InputSet,
+) -> eyre::Result<VariantRun> {
+    let Some(variants) = repo.puzzles.get(&puzzle) else {
+        eyre::bail!("{puzzle} does not exist");
+    };
     let Some(part) = variants.get(variant) else {
-        eyre::bail!("day{day}/{} was not found", variant);
+        eyre::bail!("{puzzle}.{} was not found", variant);
     };
 
-    let file_path = config
-        .input
-        .clone()
-        .unwrap_or_else(|| format!("inputs/day{day}.txt"));
+    let (input, source) = match config.input.as_deref() {
+        Some("-") => (InputData::stdin()?, "<stdin>".into()),
+        Some(path) => (InputData::read(path.as_ref(), config.mmap)?, path.into()),
+        None => {
+            if set.is_default() && !set.is_example() {
+                fetch_day(config, puzzle)?;
+            } else if !std::fs::exists(set.input_path(puzzle))? {
+                eyre::bail!("input set '{set}' has no input for {puzzle}");
+            }
+            let path = set.input_path(puzzle);
+            let input = InputData::read(&path, config.mmap)?;
+            (input, path.display().to_string())
+        }
+    };
+    let input = input.as_str();
 
-    fetch_day(day)?;
-    let input = std::fs::read_to_string(&file_path)?;
-    let mut scratch = vec![0u8; input.len()];
+    if config.validate {
+        match repo.validators.get(&puzzle) {
+            Some(validate) => validate(input).map_err(|err| {
+                eyre::eyre!("{source} does not have the shape {puzzle} expects:\n{err}")
+            })?,
+            None => status!("\x1b[33mnote\x1b[0m: {puzzle} has no input validator"),
+        }
+    }
+    let mut scratch =
+        ScratchBuffer::new(input.len(), config.scratch_align, config.scratch_padding)?;
+    let sample_count = config.sample_count(puzzle);
+    let time_limit = config.rerun_time_limit(puzzle);
 
-    println!(
-        "\x1b[32mrunning\x1b[0m [\x1b[34m{} iters\x1b[0m] day{day}/{}",
-        config.sample_count, variant
+    status!(
+        "\x1b[32mrunning\x1b[0m [\x1b[34m{} iters\x1b[0m] {puzzle}.{} [\x1b[34m{source}\x1b[0m]",
+        sample_count,
+        variant,
     );
     let mut ctx = RunContext {
-        input: &input,
-        input_scratch: &mut scratch,
+        input,
+        input_scratch: scratch.slice(),
+        visualizer: make_visualizer(config, puzzle, variant)?,
         write_output: true,
+        answer: None,
         begin_timestamp: None,
         parsed_timestamp: None,
         complete_timestamp: None,
     };
 
-    let mut samples = Vec::with_capacity(config.sample_count);
+    let mut samples = Vec::with_capacity(sample_count);
     let loop_start = Instant::now();
-    for _ in 0..config.sample_count {
-        ctx.input_scratch.clone_from_slice(input.as_bytes());
+    for _ in 0..sample_count {
+        ctx.input_scratch.restore(input.as_bytes());
 
         part(&mut ctx);
         ctx.write_output = false;
+        // only the first sample is drawn, the rest are left to be timed properly.
+        #[cfg(feature = "visualize")]
+        if let Some(writer) = ctx.visualizer.take() {
+            let (dir, frames) = writer.finish()?;
+            status!(
+                "\x1b[32mvisualized\x1b[0m {frames} frames to {}",
+                dir.display()
+            );
+        }
 
         let (start, end) = (
             ctx.begin_timestamp.unwrap(),
@@ -198,22 +655,120 @@ fn run_variant(
             // parse: ctx.parsed_timestamp.map(|ts| ts.duration_since(start)),
         });
 
-        if loop_start.elapsed() > Duration::from_secs_f64(config.rerun_time_limit_s) {
+        if loop_start.elapsed() > time_limit {
             break;
         }
     }
 
-    Ok(samples)
+    Ok(VariantRun {
+        source,
+        samples,
+        answer: ctx.answer,
+    })
 }
 
-fn parse_variant(variant: &str) -> eyre::Result<(u32, &str)> {
-    let Some((day, variant)) = variant.split_once('.') else {
-        eyre::bail!("invalid variant '{variant}'");
+/// Checks a variant's answer against the answers stored for `set`, recording it as the expected
+/// answer if asked to and there isn't one yet.
+fn check_answer(
+    config: &RunConfig,
+    set: &InputSet,
+    puzzle: Puzzle,
+    variant: &str,
+    answer: Option<&str>,
+) -> eyre::Result<Verdict> {
+    // a custom input file has nothing to do with any set's answers.
+    if config.input.is_some() {
+        return Ok(Verdict::Unknown);
+    }
+
+    let mut answers = set.load_answers(puzzle)?;
+    let verdict = inputs::verify(&answers, variant, answer);
+    if let (Verdict::Unknown, true, Some(part), Some(answer)) = (
+        &verdict,
+        config.record_answers,
+        inputs::part_of_variant(variant),
+        answer,
+    ) {
+        answers.insert(part, answer);
+        answers.save()?;
+        status!("\x1b[32mrecorded\x1b[0m answer for {puzzle}.{part} [\x1b[34m{set}\x1b[0m]");
+    }
+
+    Ok(verdict)
+}
+
+#[derive(Serialize)]
+struct ExportRecord<'a> {
+    timestamp: u64,
+    environment: &'a RunEnvironment,
+    year: u32,
+    day: u32,
+    variant: &'a str,
+    input: &'a str,
+    answer: Option<&'a str>,
+    verdict: &'a str,
+    samples: usize,
+    mean_ns: u128,
+    median_ns: u128,
+    min_ns: u128,
+    max_ns: u128,
+}
+
+/// Appends the result to the `--export` file, and prints it if the output format is JSON.
+fn record_run(
+    config: &RunConfig,
+    environment: &RunEnvironment,
+    puzzle: Puzzle,
+    variant: &str,
+    run: &VariantRun,
+    verdict: &Verdict,
+) -> eyre::Result<()> {
+    let summary = SampleSummary::summarize(&run.samples);
+    let record = ExportRecord {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        environment,
+        year: puzzle.year,
+        day: puzzle.day,
+        variant,
+        input: &run.source,
+        answer: run.answer.as_deref(),
+        verdict: verdict.name(),
+        samples: summary.count,
+        mean_ns: summary.mean.as_nanos(),
+        median_ns: summary.median.as_nanos(),
+        min_ns: summary.min.as_nanos(),
+        max_ns: summary.max.as_nanos(),
+    };
+
+    let line = serde_json::to_string(&record)?;
+
+    if let Some(path) = &config.export {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        writeln!(file, "{line}")?;
+    }
+    if config.output() == OutputFormat::Json {
+        println!("{line}");
+    }
+    Ok(())
+}
+
+fn selected_set(config: &RunConfig) -> InputSet {
+    let set = match &config.set {
+        Some(name) => InputSet::named(&config.project.inputs, name),
+        None => config.default_set(),
     };
-    let Ok(day) = day.trim_start_matches('d').parse::<u32>() else {
-        eyre::bail!("invalid day '{day}'");
+    set.with_example(config.example)
+}
+
+/// Parses `d7.part2`, or `2023.d7.part2` for a day from another event than the default one.
+fn parse_variant(variant: &str, default_year: u32) -> eyre::Result<(Puzzle, &str)> {
+    let Some((puzzle, name)) = variant.rsplit_once('.') else {
+        eyre::bail!("invalid variant '{variant}'");
     };
-    Ok((day, variant))
+    Ok((Puzzle::parse(puzzle, default_year)?, name))
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -252,54 +807,118 @@ impl SampleSummary {
 fn main() -> eyre::Result<()> {
     dotenv::dotenv().ok();
 
-    let config = RunConfig::from_args_safe()?;
-    let repo = days::make_repo();
+    let mut config = RunConfig::from_args_safe()?;
+    config.project = ProjectConfig::load(&config.config)?;
+    let pretty = config.output() == OutputFormat::Pretty;
+    STDOUT_IS_JSON.store(!pretty, Ordering::Relaxed);
+    let repo = RunnerRepository::collect()?;
+    let environment = RunEnvironment::detect();
 
     match &config.subcommand {
         RunCommand::Run { variant } => {
-            let (day, variant) = if let Some(variant) = variant {
-                parse_variant(&variant)?
+            let (puzzle, variant) = if let Some(variant) = variant {
+                parse_variant(&variant, config.year())?
             } else {
-                let max_day = repo.days.keys().max().copied().unwrap();
+                let Some(latest) = repo
+                    .puzzles
+                    .keys()
+                    .filter(|puzzle| puzzle.year == config.year())
+                    .max()
+                    .copied()
+                else {
+                    eyre::bail!("there are no days for {}", config.year());
+                };
                 let part_name = match &variant {
                     Some(name) => &*name,
                     None => {
-                        if repo.days[&max_day].contains_key("part2") {
+                        if repo.puzzles[&latest].contains_key("part2") {
                             "part2"
-                        } else if repo.days[&max_day].contains_key("part1") {
+                        } else if repo.puzzles[&latest].contains_key("part1") {
                             "part1"
                         } else {
-                            eyre::bail!("day {max_day} has no 'part1' or 'part2' specified")
+                            eyre::bail!("{latest} has no 'part1' or 'part2' specified")
                         }
                     }
                 };
-                (max_day as u32, part_name)
+                (latest, part_name)
             };
 
-            let samples = run_variant(&repo, &config, day, variant)?;
-            let summary = SampleSummary::summarize(&samples);
+            let sets = match config.all_sets {
+                true => InputSet::discover(&config.project.inputs)?
+                    .into_iter()
+                    .map(|set| set.with_example(config.example))
+                    .collect(),
+                false => vec![selected_set(&config)],
+            };
 
-            println!(
-                "[\x1b[32msamples\x1b[0m {}] [\x1b[32mmean\x1b[0m {}] [\x1b[32mmedian\x1b[0m {}] [\x1b[32mextrema\x1b[0m {} - {}]",
-                summary.count,
-                DisplayDuration(summary.mean),
-                DisplayDuration(summary.median),
-                DisplayDuration(summary.min),
-                DisplayDuration(summary.max)
-            );
+            if pretty {
+                println!("{environment}");
+            }
+
+            let mut mismatches = vec![];
+            let mut ran = 0;
+            for set in &sets {
+                // every set is tried, so one that nobody has fetched this day for is just noted.
+                if config.all_sets && !std::fs::exists(set.input_path(puzzle))? {
+                    status!("\x1b[33mmissing\x1b[0m input for {puzzle} [\x1b[34m{set}\x1b[0m]");
+                    continue;
+                }
+                ran += 1;
+                let run = run_variant(&repo, &config, puzzle, variant, set)?;
+                let summary = SampleSummary::summarize(&run.samples);
+                let verdict = check_answer(&config, set, puzzle, variant, run.answer.as_deref())?;
+                record_run(&config, &environment, puzzle, variant, &run, &verdict)?;
+
+                if pretty {
+                    println!(
+                        "[\x1b[32msamples\x1b[0m {}] [\x1b[32mmean\x1b[0m {}] [\x1b[32mmedian\x1b[0m {}] [\x1b[32mextrema\x1b[0m {} - {}] [{verdict}]",
+                        summary.count,
+                        DisplayDuration(summary.mean),
+                        DisplayDuration(summary.median),
+                        DisplayDuration(summary.min),
+                        DisplayDuration(summary.max)
+                    );
+                }
+
+                if matches!(verdict, Verdict::Incorrect { .. } | Verdict::Failed) {
+                    mismatches.push(set.to_string());
+                }
+            }
+
+            if ran == 0 {
+                eyre::bail!("none of the input sets have an input for {puzzle}");
+            }
+            if !mismatches.is_empty() {
+                eyre::bail!(
+                    "{puzzle}.{variant} gave a wrong answer for input sets: {}",
+                    mismatches.join(", ")
+                );
+            }
         }
         RunCommand::Compare { variant1, variant2 } => {
             // it might be cool to benchmark by continually starting child processes and using ipc
             // to get the results, so that funky stuff like code pages being better or worse aligned
             // doesnt muddy the results as much. but idk how significant the effect of that stuff is.
 
-            let (day1, part1) = parse_variant(&variant1)?;
-            let (day2, part2) = parse_variant(&variant2)?;
-            let samples1 = run_variant(&repo, &config, day1, part1)?;
-            let samples2 = run_variant(&repo, &config, day2, part2)?;
+            let (puzzle1, part1) = parse_variant(&variant1, config.year())?;
+            let (puzzle2, part2) = parse_variant(&variant2, config.year())?;
+            let set = selected_set(&config);
+            if pretty {
+                println!("{environment}");
+            }
+            let run1 = run_variant(&repo, &config, puzzle1, part1, &set)?;
+            let run2 = run_variant(&repo, &config, puzzle2, part2, &set)?;
+
+            let verdict1 = check_answer(&config, &set, puzzle1, part1, run1.answer.as_deref())?;
+            let verdict2 = check_answer(&config, &set, puzzle2, part2, run2.answer.as_deref())?;
+            record_run(&config, &environment, puzzle1, part1, &run1, &verdict1)?;
+            record_run(&config, &environment, puzzle2, part2, &run2, &verdict2)?;
+            if !pretty {
+                return Ok(());
+            }
 
-            let summary1 = SampleSummary::summarize(&samples1);
-            let summary2 = SampleSummary::summarize(&samples2);
+            let summary1 = SampleSummary::summarize(&run1.samples);
+            let summary2 = SampleSummary::summarize(&run2.samples);
 
             fn hl(x: bool) -> &'static str {
                 match x {
@@ -309,7 +928,7 @@ fn main() -> eyre::Result<()> {
             }
 
             println!(
-                "[\x1b[32msamples\x1b[0m {}] [\x1b[32mmean\x1b[0m {}{}\x1b[0m] [\x1b[32mmedian\x1b[0m {}{}\x1b[0m] [\x1b[32mextrema\x1b[0m {} - {}] \x1b[34m{variant1}\x1b[0m",
+                "[\x1b[32msamples\x1b[0m {}] [\x1b[32mmean\x1b[0m {}{}\x1b[0m] [\x1b[32mmedian\x1b[0m {}{}\x1b[0m] [\x1b[32mextrema\x1b[0m {} - {}] \x1b[34m{variant1}\x1b[0m [{verdict1}]",
                 summary1.count,
                 hl(summary1.mean < summary2.mean),
                 DisplayDuration(summary1.mean),
@@ -320,7 +939,7 @@ fn main() -> eyre::Result<()> {
             );
 
             println!(
-                "[\x1b[32msamples\x1b[0m {}] [\x1b[32mmean\x1b[0m {}{}\x1b[0m] [\x1b[32mmedian\x1b[0m {}{}\x1b[0m] [\x1b[32mextrema\x1b[0m {} - {}] \x1b[34m{variant2}\x1b[0m",
+                "[\x1b[32msamples\x1b[0m {}] [\x1b[32mmean\x1b[0m {}{}\x1b[0m] [\x1b[32mmedian\x1b[0m {}{}\x1b[0m] [\x1b[32mextrema\x1b[0m {} - {}] \x1b[34m{variant2}\x1b[0m [{verdict2}]",
                 summary2.count,
                 hl(summary2.mean < summary1.mean),
                 DisplayDuration(summary2.mean),
@@ -332,18 +951,34 @@ fn main() -> eyre::Result<()> {
         }
         RunCommand::List => {
             println!("Available Variants:");
-            let mut days: Vec<_> = repo.days.keys().copied().collect();
-            days.sort_unstable();
-            for day in days {
-                let mut variants: Vec<_> = repo.days[&day].keys().collect();
+            let mut puzzles: Vec<_> = repo.puzzles.keys().copied().collect();
+            puzzles.sort_unstable();
+            for puzzle in puzzles {
+                let mut variants: Vec<_> = repo.puzzles[&puzzle].keys().collect();
                 variants.sort_unstable();
                 for variant in variants {
-                    println!("\t- d{day}.{variant}");
+                    println!("\t- {puzzle}.{variant}");
                 }
             }
         }
         &RunCommand::Fetch { day } => {
-            fetch_day(day)?;
+            let year = config.year();
+            fetch_day(&config, Puzzle { year, day })?;
+        }
+        &RunCommand::FetchExamples { day } => {
+            let year = config.year();
+            fetch_examples(&config, Puzzle { year, day })?;
+        }
+        &RunCommand::New { day } => {
+            let year = config.year();
+            new_day(&repo, Puzzle { year, day })?;
+        }
+        RunCommand::Submit { day, part, variant } => {
+            let puzzle = Puzzle {
+                year: config.year(),
+                day: *day,
+            };
+            submit_variant(&repo, &config, puzzle, *part, variant.as_deref())?;
         }
     }
 
@@ -384,3 +1019,51 @@ where
         self.0(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_variants() {
+        let repo = RunnerRepository::collect().unwrap();
+        let day1 = Puzzle { year: 2024, day: 1 };
+        assert_eq!(
+            repo.puzzles
+                .get(&day1)
+                .is_some_and(|variants| variants.contains_key("part1")),
+            cfg!(feature = "day1")
+        );
+        assert_eq!(
+            puzzle_of_entry("aoc2024::days::day17", None, None),
+            Some(Puzzle {
+                year: 2024,
+                day: 17
+            })
+        );
+        assert_eq!(
+            puzzle_of_entry("aoc2024::days::year2023::day3", None, None),
+            Some(Puzzle { year: 2023, day: 3 })
+        );
+        assert_eq!(puzzle_of_entry("aoc2024::prelude", None, None), None);
+    }
+
+    #[test]
+    fn test_parse_variant() {
+        let puzzle = |year, day| Puzzle { year, day };
+        assert_eq!(
+            parse_variant("d7.part2", 2024).unwrap(),
+            (puzzle(2024, 7), "part2")
+        );
+        assert_eq!(
+            parse_variant("7.part2", 2024).unwrap(),
+            (puzzle(2024, 7), "part2")
+        );
+        assert_eq!(
+            parse_variant("2023.d7.part2_fast", 2024).unwrap(),
+            (puzzle(2023, 7), "part2_fast")
+        );
+        assert!(parse_variant("next.d7.part2", 2024).is_err());
+        assert!(parse_variant("part2", 2024).is_err());
+    }
+}
@@ -0,0 +1,858 @@
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Bound, RangeBounds,
+};
+
+use crate::bitset::{Bits, Bitset};
+
+/// The most values an array container holds before it turns into a bitmap. Past this, the
+/// bitmap's fixed 8KiB is smaller than two bytes per value.
+const ARRAY_MAX: usize = 4096;
+/// The words in a bitmap container, enough for every low half of a value.
+const BITMAP_WORDS: usize = 1 << 16 >> 6;
+const HI64: u64 = 1u64 << 63;
+
+/// A compressed set of `u32`s, for sets that are spread over a huge range but sparse.
+///
+/// Values are split into chunks by their top 16 bits, and each chunk that has anything in it picks
+/// whichever of a sorted array, a 2^16 bit bitmap or a list of runs is smallest for what it holds.
+/// A [`Bitset`] over the same values would take `max / 8` bytes no matter how few there are, so
+/// [`size_in_bytes`](Self::size_in_bytes) against that is how to tell which one to use.
+///
+/// The queries and set algebra match [`Bits`] and [`RankSelect`](crate::bitset::RankSelect), except
+/// that there's no length: every `u32` is in range. So there's no `len` or `count_zeros`, and zeros
+/// are only counted within a range, with [`count_zeros_in`](Self::count_zeros_in).
+#[derive(Clone, Debug, Default)]
+pub struct Roaring {
+    // sorted by key, and never holding an empty container.
+    chunks: Vec<(u16, Container)>,
+}
+
+#[derive(Clone, Debug)]
+enum Container {
+    /// The values, in increasing order. Never more than `ARRAY_MAX` of them.
+    Array(Vec<u16>),
+    /// Every value in the chunk, msb-first like `Bitset`, along with how many ones there are.
+    Bitmap(Box<[u64; BITMAP_WORDS]>, u32),
+    /// `start..=end` pairs, in increasing order. Only made by `optimize`, and turned back into
+    /// one of the others as soon as anything changes.
+    Run(Vec<(u16, u16)>),
+}
+
+fn split(ix: u32) -> (u16, u16) {
+    ((ix >> 16) as u16, ix as u16)
+}
+
+fn join(key: u16, low: u16) -> u32 {
+    ((key as u32) << 16) | low as u32
+}
+
+/// The set operations, described both per word and per value for the containers that don't
+/// have words.
+#[derive(Copy, Clone)]
+enum Op {
+    And,
+    Or,
+    Xor,
+    AndNot,
+}
+
+impl Op {
+    fn words(self, lhs: u64, rhs: u64) -> u64 {
+        match self {
+            Op::And => lhs & rhs,
+            Op::Or => lhs | rhs,
+            Op::Xor => lhs ^ rhs,
+            Op::AndNot => lhs & !rhs,
+        }
+    }
+
+    /// Whether a value that is (or isn't) in either side ends up in the result.
+    fn keeps(self, in_lhs: bool, in_rhs: bool) -> bool {
+        self.words(in_lhs as u64, in_rhs as u64) != 0
+    }
+}
+
+impl Container {
+    /// Picks an array or a bitmap for `values`, which have to be sorted.
+    fn from_values(values: Vec<u16>) -> Option<Container> {
+        match values.len() {
+            0 => None,
+            n if n <= ARRAY_MAX => Some(Container::Array(values)),
+            _ => {
+                let mut words = Box::new([0u64; BITMAP_WORDS]);
+                values.iter().for_each(|&low| set_bit(&mut words, low));
+                Some(Container::Bitmap(words, values.len() as u32))
+            }
+        }
+    }
+
+    /// Picks an array or a bitmap for the values set in `words`.
+    fn from_words(words: Box<[u64; BITMAP_WORDS]>) -> Option<Container> {
+        match words.iter().map(|word| word.count_ones()).sum::<u32>() {
+            0 => None,
+            n if n as usize <= ARRAY_MAX => Some(Container::Array(WordIter::new(&words).collect())),
+            n => Some(Container::Bitmap(words, n)),
+        }
+    }
+
+    fn len(&self) -> u32 {
+        match self {
+            Container::Array(values) => values.len() as u32,
+            Container::Bitmap(_, ones) => *ones,
+            Container::Run(runs) => runs
+                .iter()
+                .map(|&(start, end)| (end - start) as u32 + 1)
+                .sum(),
+        }
+    }
+
+    /// The bytes this takes up on the heap.
+    fn size_in_bytes(&self) -> usize {
+        match self {
+            Container::Array(values) => 2 * values.len(),
+            Container::Bitmap(..) => 8 * BITMAP_WORDS,
+            Container::Run(runs) => 4 * runs.len(),
+        }
+    }
+
+    fn contains(&self, low: u16) -> bool {
+        match self {
+            Container::Array(values) => values.binary_search(&low).is_ok(),
+            Container::Bitmap(words, _) => words[low as usize >> 6] & (HI64 >> (low & 63)) != 0,
+            Container::Run(runs) => {
+                let ix = runs.partition_point(|&(_, end)| end < low);
+                runs.get(ix).is_some_and(|&(start, _)| start <= low)
+            }
+        }
+    }
+
+    fn words(&self) -> Box<[u64; BITMAP_WORDS]> {
+        let mut words = Box::new([0u64; BITMAP_WORDS]);
+        match self {
+            Container::Bitmap(bitmap, _) => words.copy_from_slice(&bitmap[..]),
+            _ => self.iter().for_each(|low| set_bit(&mut words, low)),
+        }
+        words
+    }
+
+    /// Turns a run container back into one that can be changed.
+    fn unrun(&mut self) {
+        if let Container::Run(_) = self {
+            *self = Container::from_words(self.words()).expect("run containers are never empty");
+        }
+    }
+
+    fn insert(&mut self, low: u16) {
+        self.unrun();
+        match self {
+            Container::Array(values) => match values.binary_search(&low) {
+                Ok(_) => {}
+                Err(_) if values.len() == ARRAY_MAX => {
+                    let mut words = self.words();
+                    set_bit(&mut words, low);
+                    *self = Container::Bitmap(words, ARRAY_MAX as u32 + 1);
+                }
+                Err(ix) => values.insert(ix, low),
+            },
+            Container::Bitmap(words, ones) => {
+                let bit = HI64 >> (low & 63);
+                let word = &mut words[low as usize >> 6];
+                *ones += (*word & bit == 0) as u32;
+                *word |= bit;
+            }
+            Container::Run(_) => unreachable!(),
+        }
+    }
+
+    fn remove(&mut self, low: u16) {
+        self.unrun();
+        match self {
+            Container::Array(values) => {
+                if let Ok(ix) = values.binary_search(&low) {
+                    values.remove(ix);
+                }
+            }
+            Container::Bitmap(words, ones) => {
+                let bit = HI64 >> (low & 63);
+                let word = &mut words[low as usize >> 6];
+                *ones -= (*word & bit != 0) as u32;
+                *word &= !bit;
+                if *ones as usize <= ARRAY_MAX {
+                    *self = Container::Array(self.iter().collect());
+                }
+            }
+            Container::Run(_) => unreachable!(),
+        }
+    }
+
+    /// The first value that's at least `low`, which can be up to 2^16.
+    fn next_from(&self, low: u32) -> Option<u16> {
+        match self {
+            Container::Array(values) => {
+                let ix = values.partition_point(|&value| (value as u32) < low);
+                values.get(ix).copied()
+            }
+            Container::Bitmap(words, _) => {
+                let mut word_ix = low as usize >> 6;
+                let mut mask = u64::MAX >> (low & 63);
+                while word_ix < BITMAP_WORDS {
+                    let word = words[word_ix] & mask;
+                    if word != 0 {
+                        return Some(((word_ix << 6) + word.leading_zeros() as usize) as u16);
+                    }
+                    word_ix += 1;
+                    mask = u64::MAX;
+                }
+                None
+            }
+            Container::Run(runs) => {
+                let ix = runs.partition_point(|&(_, end)| (end as u32) < low);
+                runs.get(ix)
+                    .map(|&(start, _)| (start as u32).max(low) as u16)
+            }
+        }
+    }
+
+    /// The number of values below `low`, which can be up to 2^16.
+    fn rank(&self, low: u32) -> u32 {
+        match self {
+            Container::Array(values) => {
+                values.partition_point(|&value| (value as u32) < low) as u32
+            }
+            Container::Bitmap(words, _) => {
+                let word_ix = low as usize >> 6;
+                let whole: u32 = words[..word_ix].iter().map(|word| word.count_ones()).sum();
+                let partial = match low & 63 {
+                    0 => 0,
+                    n => (words[word_ix] & (u64::MAX << (64 - n))).count_ones(),
+                };
+                whole + partial
+            }
+            Container::Run(runs) => runs
+                .iter()
+                .take_while(|&&(start, _)| (start as u32) < low)
+                .map(|&(start, end)| (end as u32 + 1).min(low) - start as u32)
+                .sum(),
+        }
+    }
+
+    /// The `k`th value, counting from zero. `k` has to be less than `len`.
+    fn select(&self, mut k: u32) -> u16 {
+        match self {
+            Container::Array(values) => values[k as usize],
+            Container::Bitmap(words, _) => {
+                for (word_ix, &word) in words.iter().enumerate() {
+                    let ones = word.count_ones();
+                    if k < ones {
+                        let mut word = word;
+                        for _ in 0..k {
+                            word &= !(HI64 >> word.leading_zeros());
+                        }
+                        return ((word_ix << 6) + word.leading_zeros() as usize) as u16;
+                    }
+                    k -= ones;
+                }
+                unreachable!("k is past the end of the container")
+            }
+            Container::Run(runs) => {
+                for &(start, end) in runs {
+                    let len = (end - start) as u32 + 1;
+                    if k < len {
+                        return start + k as u16;
+                    }
+                    k -= len;
+                }
+                unreachable!("k is past the end of the container")
+            }
+        }
+    }
+
+    fn iter(&self) -> ContainerIter<'_> {
+        match self {
+            Container::Array(values) => ContainerIter::Array(values.iter()),
+            Container::Bitmap(words, _) => ContainerIter::Bitmap(WordIter::new(words)),
+            Container::Run(runs) => ContainerIter::Run(runs.iter(), None),
+        }
+    }
+
+    fn combine(&self, rhs: &Container, op: Op) -> Option<Container> {
+        match (self, rhs) {
+            (Container::Array(lhs), Container::Array(rhs)) => {
+                Container::from_values(merge(lhs, rhs, op))
+            }
+            _ => {
+                let mut words = self.words();
+                let rhs = rhs.words();
+                for (word, &rhs) in words.iter_mut().zip(rhs.iter()) {
+                    *word = op.words(*word, rhs);
+                }
+                Container::from_words(words)
+            }
+        }
+    }
+
+    /// Switches to runs if they'd be smaller than what's there now, or from runs to whatever
+    /// else would be.
+    fn optimize(&mut self) {
+        let mut runs: Vec<(u16, u16)> = vec![];
+        for low in self.iter() {
+            match runs.last_mut() {
+                Some((_, end)) if *end as u32 + 1 == low as u32 => *end = low,
+                _ => runs.push((low, low)),
+            }
+        }
+        let len = self.len() as usize;
+        let flat_size = if len <= ARRAY_MAX {
+            2 * len
+        } else {
+            8 * BITMAP_WORDS
+        };
+        if 4 * runs.len() < flat_size {
+            *self = Container::Run(runs);
+        } else {
+            self.unrun();
+        }
+    }
+}
+
+/// `range` as `start..end`, where `end` can be up to 2^32.
+#[track_caller]
+fn resolve_range(range: impl RangeBounds<u32>) -> (u64, u64) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start as u64,
+        Bound::Excluded(&start) => start as u64 + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end as u64 + 1,
+        Bound::Excluded(&end) => end as u64,
+        Bound::Unbounded => 1 << 32,
+    };
+    assert!(start <= end, "range start {start} is past its end {end}");
+    (start, end)
+}
+
+fn set_bit(words: &mut [u64; BITMAP_WORDS], low: u16) {
+    words[low as usize >> 6] |= HI64 >> (low & 63);
+}
+
+/// Walks two sorted lists together, keeping the values that `op` says belong in the result.
+fn merge(lhs: &[u16], rhs: &[u16], op: Op) -> Vec<u16> {
+    let mut out = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < lhs.len() || j < rhs.len() {
+        let (value, in_lhs, in_rhs) = match (lhs.get(i), rhs.get(j)) {
+            (Some(&l), Some(&r)) if l == r => (l, true, true),
+            (Some(&l), Some(&r)) if l < r => (l, true, false),
+            (Some(&l), None) => (l, true, false),
+            (_, Some(&r)) => (r, false, true),
+            (None, None) => unreachable!(),
+        };
+        i += in_lhs as usize;
+        j += in_rhs as usize;
+        if op.keeps(in_lhs, in_rhs) {
+            out.push(value);
+        }
+    }
+    out
+}
+
+/// The set bits of a bitmap container, in increasing order.
+#[derive(Clone, Debug)]
+struct WordIter<'a> {
+    words: &'a [u64; BITMAP_WORDS],
+    word_ix: usize,
+    // what's left of `words[word_ix]`.
+    word: u64,
+}
+
+impl<'a> WordIter<'a> {
+    fn new(words: &'a [u64; BITMAP_WORDS]) -> Self {
+        Self {
+            words,
+            word_ix: 0,
+            word: words[0],
+        }
+    }
+}
+
+impl Iterator for WordIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        while self.word == 0 {
+            self.word_ix += 1;
+            self.word = *self.words.get(self.word_ix)?;
+        }
+        let bit = self.word.leading_zeros();
+        self.word &= !(HI64 >> bit);
+        Some(((self.word_ix << 6) + bit as usize) as u16)
+    }
+}
+
+#[derive(Clone, Debug)]
+enum ContainerIter<'a> {
+    Array(std::slice::Iter<'a, u16>),
+    Bitmap(WordIter<'a>),
+    // the rest of the run being walked, as `next..=end`.
+    Run(std::slice::Iter<'a, (u16, u16)>, Option<(u32, u16)>),
+}
+
+impl Iterator for ContainerIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        match self {
+            ContainerIter::Array(values) => values.next().copied(),
+            ContainerIter::Bitmap(words) => words.next(),
+            ContainerIter::Run(runs, current) => {
+                let (next, end) = match *current {
+                    Some((next, end)) if next <= end as u32 => (next, end),
+                    _ => runs.next().map(|&(start, end)| (start as u32, end))?,
+                };
+                *current = Some((next + 1, end));
+                Some(next as u16)
+            }
+        }
+    }
+}
+
+/// The values in a [`Roaring`], in increasing order.
+#[derive(Clone, Debug)]
+pub struct Iter<'a> {
+    chunks: std::slice::Iter<'a, (u16, Container)>,
+    current: Option<(u16, ContainerIter<'a>)>,
+}
+
+impl Iterator for Iter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some((key, values)) = &mut self.current {
+                if let Some(low) = values.next() {
+                    return Some(join(*key, low));
+                }
+            }
+            let (key, container) = self.chunks.next()?;
+            self.current = Some((*key, container.iter()));
+        }
+    }
+}
+
+impl Roaring {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn chunk(&self, key: u16) -> Result<usize, usize> {
+        self.chunks.binary_search_by_key(&key, |&(key, _)| key)
+    }
+
+    pub fn get(&self, ix: u32) -> bool {
+        let (key, low) = split(ix);
+        self.chunk(key)
+            .is_ok_and(|chunk| self.chunks[chunk].1.contains(low))
+    }
+
+    pub fn set(&mut self, ix: u32) {
+        let (key, low) = split(ix);
+        match self.chunk(key) {
+            Ok(chunk) => self.chunks[chunk].1.insert(low),
+            Err(chunk) => self
+                .chunks
+                .insert(chunk, (key, Container::Array(vec![low]))),
+        }
+    }
+
+    pub fn clear(&mut self, ix: u32) {
+        let (key, low) = split(ix);
+        if let Ok(chunk) = self.chunk(key) {
+            let container = &mut self.chunks[chunk].1;
+            container.remove(low);
+            if container.len() == 0 {
+                self.chunks.remove(chunk);
+            }
+        }
+    }
+
+    pub fn clear_all(&mut self) {
+        self.chunks.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    pub fn count_ones(&self) -> u64 {
+        self.chunks
+            .iter()
+            .map(|(_, container)| container.len() as u64)
+            .sum()
+    }
+
+    /// Roughly how much memory the set takes, for comparing against a [`Bitset`].
+    pub fn size_in_bytes(&self) -> usize {
+        let containers: usize = self
+            .chunks
+            .iter()
+            .map(|(_, container)| container.size_in_bytes())
+            .sum();
+        containers + self.chunks.len() * std::mem::size_of::<(u16, Container)>()
+    }
+
+    /// Switches each chunk over to runs where they're smaller. Anything that changes a chunk
+    /// afterwards switches it back, so this is for once the set is done being built.
+    pub fn optimize(&mut self) {
+        self.chunks
+            .iter_mut()
+            .for_each(|(_, container)| container.optimize());
+    }
+
+    /// The values in the set, in increasing order.
+    pub fn iter_ones(&self) -> Iter<'_> {
+        Iter {
+            chunks: self.chunks.iter(),
+            current: None,
+        }
+    }
+
+    pub fn first_one(&self) -> Option<u32> {
+        self.iter_ones().next()
+    }
+
+    /// The first value in the set strictly after `ix`.
+    pub fn next_one_after(&self, ix: u32) -> Option<u32> {
+        let ix = ix.checked_add(1)?;
+        let (key, low) = split(ix);
+        let start = self.chunk(key).unwrap_or_else(|chunk| chunk);
+        self.chunks[start..]
+            .iter()
+            .find_map(|(chunk_key, container)| {
+                let from = if *chunk_key == key { low as u32 } else { 0 };
+                container.next_from(from).map(|low| join(*chunk_key, low))
+            })
+    }
+
+    /// The number of values below `ix`. `ix` can be up to 2^32, so `rank(1 << 32)` is the total.
+    pub fn rank(&self, ix: u64) -> u64 {
+        assert!(ix <= 1 << 32, "ix={ix} is past every u32");
+        let key = (ix >> 16) as u32;
+        self.chunks
+            .iter()
+            .take_while(|&&(chunk_key, _)| (chunk_key as u32) <= key)
+            .map(|(chunk_key, container)| match *chunk_key as u32 == key {
+                true => container.rank(ix as u32 & 0xffff) as u64,
+                false => container.len() as u64,
+            })
+            .sum()
+    }
+
+    /// The `k`th value in the set, counting from zero, so that `rank(select(k)) == k`.
+    pub fn select(&self, mut k: u64) -> Option<u32> {
+        for (key, container) in &self.chunks {
+            let len = container.len() as u64;
+            if k < len {
+                return Some(join(*key, container.select(k as u32)));
+            }
+            k -= len;
+        }
+        None
+    }
+
+    pub fn count_ones_in(&self, range: impl RangeBounds<u32>) -> u64 {
+        let (start, end) = resolve_range(range);
+        self.rank(end) - self.rank(start)
+    }
+
+    pub fn count_zeros_in(&self, range: impl RangeBounds<u32>) -> u64 {
+        let (start, end) = resolve_range(range);
+        (end - start) - (self.rank(end) - self.rank(start))
+    }
+
+    /// Whether any value in `range` is in the set. Always false for an empty range.
+    pub fn any_in(&self, range: impl RangeBounds<u32>) -> bool {
+        let (start, end) = resolve_range(range);
+        let next = match start {
+            0 => self.first_one(),
+            start => self.next_one_after(start as u32 - 1),
+        };
+        next.is_some_and(|ix| (ix as u64) < end)
+    }
+
+    /// Whether every value in `range` is in the set. Always true for an empty range.
+    pub fn all_in(&self, range: impl RangeBounds<u32>) -> bool {
+        let (start, end) = resolve_range(range);
+        self.rank(end) - self.rank(start) == end - start
+    }
+
+    fn combine(&self, rhs: &Roaring, op: Op) -> Roaring {
+        let mut chunks = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.chunks.len() || j < rhs.chunks.len() {
+            let lhs_chunk = self.chunks.get(i);
+            let rhs_chunk = rhs.chunks.get(j);
+            match (lhs_chunk, rhs_chunk) {
+                (Some((l, lhs)), Some((r, rhs))) if l == r => {
+                    chunks.extend(lhs.combine(rhs, op).map(|container| (*l, container)));
+                    i += 1;
+                    j += 1;
+                }
+                (Some((l, lhs)), Some((r, _))) if l < r => {
+                    if op.keeps(true, false) {
+                        chunks.push((*l, lhs.clone()));
+                    }
+                    i += 1;
+                }
+                (Some((l, lhs)), None) => {
+                    if op.keeps(true, false) {
+                        chunks.push((*l, lhs.clone()));
+                    }
+                    i += 1;
+                }
+                (_, Some((r, rhs))) => {
+                    if op.keeps(false, true) {
+                        chunks.push((*r, rhs.clone()));
+                    }
+                    j += 1;
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+        Roaring { chunks }
+    }
+
+    /// The values in `self` that aren't in `rhs`.
+    pub fn and_not(&self, rhs: &Roaring) -> Roaring {
+        self.combine(rhs, Op::AndNot)
+    }
+
+    pub fn and_not_assign(&mut self, rhs: &Roaring) {
+        *self = self.and_not(rhs);
+    }
+
+    /// Whether every value in `self` is also in `other`.
+    pub fn is_subset(&self, other: &Roaring) -> bool {
+        self.and_not(other).is_empty()
+    }
+
+    pub fn is_superset(&self, other: &Roaring) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Whether no value is in both `self` and `other`.
+    pub fn is_disjoint(&self, other: &Roaring) -> bool {
+        self.combine(other, Op::And).is_empty()
+    }
+
+    /// A dense copy of the set, `len` bits long. Every value has to be below `len`.
+    pub fn to_bitset(&self, len: usize) -> Bitset {
+        if let Some((key, container)) = self.chunks.last() {
+            let last = join(*key, container.iter().last().unwrap());
+            assert!((last as usize) < len, "{last} doesn't fit in {len} bits");
+        }
+        let mut bitset = Bitset::new(len);
+        for (key, container) in &self.chunks {
+            match container {
+                Container::Bitmap(words, _) => {
+                    // every value fits, so the words past the end of the bitset are all empty.
+                    let dst = &mut bitset.bits_mut()[*key as usize * BITMAP_WORDS..];
+                    let count = dst.len().min(BITMAP_WORDS);
+                    dst[..count].copy_from_slice(&words[..count]);
+                }
+                _ => container
+                    .iter()
+                    .for_each(|low| bitset.set(join(*key, low) as usize)),
+            }
+        }
+        bitset
+    }
+}
+
+impl From<&Bitset> for Roaring {
+    fn from(bitset: &Bitset) -> Self {
+        // the last bit has to be a `u32`. `1 << 32` itself doesn't fit in a 32-bit `usize`.
+        assert!(
+            u32::try_from(bitset.len().saturating_sub(1)).is_ok(),
+            "too long to hold in a Roaring"
+        );
+        let mut chunks = vec![];
+        let tail_bits = bitset.len() & 63;
+        let word_count = bitset.bits().len();
+        for (key, chunk) in bitset.bits().chunks(BITMAP_WORDS).enumerate() {
+            let mut words = Box::new([0u64; BITMAP_WORDS]);
+            words[..chunk.len()].copy_from_slice(chunk);
+            // bits past `len` aren't part of the set.
+            let last = word_count - key * BITMAP_WORDS - 1;
+            if tail_bits != 0 && last < BITMAP_WORDS {
+                words[last] &= u64::MAX << (64 - tail_bits);
+            }
+            chunks.extend(Container::from_words(words).map(|container| (key as u16, container)));
+        }
+        Roaring { chunks }
+    }
+}
+
+impl FromIterator<u32> for Roaring {
+    fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
+        let mut roaring = Roaring::new();
+        roaring.extend(iter);
+        roaring
+    }
+}
+
+impl Extend<u32> for Roaring {
+    fn extend<T: IntoIterator<Item = u32>>(&mut self, iter: T) {
+        iter.into_iter().for_each(|ix| self.set(ix));
+    }
+}
+
+// the same values can be held in different kinds of container, so compare what's in them.
+impl PartialEq for Roaring {
+    fn eq(&self, other: &Self) -> bool {
+        self.chunks.len() == other.chunks.len()
+            && self
+                .chunks
+                .iter()
+                .zip(&other.chunks)
+                .all(|((l, lhs), (r, rhs))| {
+                    l == r && lhs.len() == rhs.len() && lhs.iter().eq(rhs.iter())
+                })
+    }
+}
+
+impl Eq for Roaring {}
+
+macro_rules! roaring_op_impl {
+    ($trait:ident, $binop:ident, $assign_trait:ident, $assign:ident, $op:expr) => {
+        impl $trait<&Roaring> for &Roaring {
+            type Output = Roaring;
+            fn $binop(self, rhs: &Roaring) -> Roaring {
+                self.combine(rhs, $op)
+            }
+        }
+
+        impl $assign_trait<&Roaring> for Roaring {
+            fn $assign(&mut self, rhs: &Roaring) {
+                *self = self.combine(rhs, $op);
+            }
+        }
+    };
+}
+
+roaring_op_impl!(BitAnd, bitand, BitAndAssign, bitand_assign, Op::And);
+roaring_op_impl!(BitOr, bitor, BitOrAssign, bitor_assign, Op::Or);
+roaring_op_impl!(BitXor, bitxor, BitXorAssign, bitxor_assign, Op::Xor);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::collections::BTreeSet;
+
+    /// Values that land in every kind of container: a few scattered anywhere, a dense block that
+    /// needs a bitmap, and some long runs.
+    fn values() -> impl Strategy<Value = Vec<u32>> {
+        let scattered = prop::collection::vec(any::<u32>(), 0..50);
+        let dense =
+            (0u32..4, prop::collection::vec(0u32..1 << 16, 0..6000)).prop_map(|(key, lows)| {
+                lows.into_iter()
+                    .map(|low| key << 16 | low)
+                    .collect::<Vec<_>>()
+            });
+        let runs = prop::collection::vec((0u32..1 << 18, 0u32..3000), 0..4).prop_map(|runs| {
+            runs.into_iter()
+                .flat_map(|(start, len)| start..start + len)
+                .collect::<Vec<_>>()
+        });
+        (scattered, dense, runs).prop_map(|(a, b, c)| [a, b, c].concat())
+    }
+
+    fn check(roaring: &Roaring, model: &BTreeSet<u32>) -> Result<(), TestCaseError> {
+        prop_assert_eq!(roaring.count_ones(), model.len() as u64);
+        prop_assert!(roaring.iter_ones().eq(model.iter().copied()));
+        prop_assert_eq!(roaring.is_empty(), model.is_empty());
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn prop_against_model(lhs in values(), rhs in values(), removed in values(), optimize: (bool, bool)) {
+            let mut a = lhs.iter().copied().collect::<Roaring>();
+            let mut b = rhs.iter().copied().collect::<Roaring>();
+            let mut lhs = lhs.into_iter().collect::<BTreeSet<_>>();
+            let rhs = rhs.into_iter().collect::<BTreeSet<_>>();
+            for &ix in &removed {
+                a.clear(ix);
+                lhs.remove(&ix);
+            }
+            if optimize.0 {
+                a.optimize();
+            }
+            if optimize.1 {
+                b.optimize();
+            }
+            check(&a, &lhs)?;
+            check(&b, &rhs)?;
+
+            for &ix in removed.iter().chain(&[0, u32::MAX]) {
+                prop_assert_eq!(a.get(ix), lhs.contains(&ix));
+                let next = ix.checked_add(1).and_then(|from| lhs.range(from..).next().copied());
+                prop_assert_eq!(a.next_one_after(ix), next);
+            }
+            prop_assert_eq!(a.first_one(), lhs.first().copied());
+
+            let ones = lhs.iter().copied().collect::<Vec<_>>();
+            for (k, &ix) in ones.iter().enumerate().step_by(ones.len() / 64 + 1) {
+                prop_assert_eq!(a.select(k as u64), Some(ix));
+                prop_assert_eq!(a.rank(ix as u64), k as u64);
+            }
+            prop_assert_eq!(a.select(ones.len() as u64), None);
+            prop_assert_eq!(a.rank(1 << 32), ones.len() as u64);
+            for pair in removed.windows(2).take(64).chain([&[0, u32::MAX][..]]) {
+                let (start, end) = (pair[0].min(pair[1]), pair[0].max(pair[1]));
+                let count = lhs.range(start..end).count() as u64;
+                prop_assert_eq!(a.count_ones_in(start..end), count);
+                prop_assert_eq!(a.count_zeros_in(start..=end), (end - start) as u64 + 1 - count - lhs.contains(&end) as u64);
+                prop_assert_eq!(a.any_in(start..end), count > 0);
+                prop_assert_eq!(a.all_in(start..end), count == (end - start) as u64);
+            }
+
+            check(&(&a & &b), &(&lhs & &rhs))?;
+            check(&(&a | &b), &(&lhs | &rhs))?;
+            check(&(&a ^ &b), &(&lhs ^ &rhs))?;
+            check(&a.and_not(&b), &(&lhs - &rhs))?;
+            let mut assigned = a.clone();
+            assigned |= &b;
+            check(&assigned, &(&lhs | &rhs))?;
+
+            prop_assert_eq!(a.is_subset(&b), lhs.is_subset(&rhs));
+            prop_assert_eq!(a.is_disjoint(&b), lhs.is_disjoint(&rhs));
+            prop_assert!((&a & &b).is_subset(&a));
+            prop_assert_eq!(a == b, lhs == rhs);
+            prop_assert_eq!(&a, &lhs.iter().copied().collect::<Roaring>());
+
+            // changing an optimized set still works.
+            let mut changed = a.clone();
+            changed.set(12345);
+            changed.clear(3);
+            let mut expected = lhs.clone();
+            expected.insert(12345);
+            expected.remove(&3);
+            check(&changed, &expected)?;
+        }
+
+        #[test]
+        fn prop_bitset_round_trip(model in prop::collection::vec(any::<bool>(), 0..200_000)) {
+            let bitset = model.iter().copied().collect::<Bitset>();
+            let mut roaring = Roaring::from(&bitset);
+            let ones = (0..model.len() as u32).filter(|&i| model[i as usize]).collect::<BTreeSet<_>>();
+            check(&roaring, &ones)?;
+            prop_assert_eq!(roaring.to_bitset(model.len()), bitset.clone());
+            roaring.optimize();
+            prop_assert_eq!(roaring.to_bitset(model.len()), bitset);
+        }
+    }
+}
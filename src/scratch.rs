@@ -0,0 +1,127 @@
+use std::{
+    alloc::Layout,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+/// Variants can always rely on at least this much padding, which is enough for one full 512-bit
+/// vector load starting anywhere inside the input.
+pub const MIN_PADDING: usize = 64;
+
+/// A zeroed, aligned allocation that holds a copy of the input, followed by at least `padding`
+/// zero bytes.
+///
+/// The padding means SIMD code can do full-width loads that run off the end of the input without
+/// needing a separate loop for the tail.
+pub struct ScratchBuffer {
+    ptr: NonNull<u8>,
+    layout: Layout,
+    len: usize,
+}
+
+impl ScratchBuffer {
+    pub fn new(len: usize, align: usize, padding: usize) -> eyre::Result<Self> {
+        // checked up front, since rounding up to a multiple of 0 panics before `Layout` can
+        // complain about it.
+        if !align.is_power_of_two() {
+            eyre::bail!("invalid scratch alignment {align}, must be a power of two");
+        }
+        // round up so the padded end is aligned too, and a wide load from the last aligned
+        // position can't run past the allocation.
+        let padding = padding.max(MIN_PADDING);
+        let size = (len + padding).next_multiple_of(align).max(align);
+        let layout = Layout::from_size_align(size, align)?;
+        let Some(ptr) = NonNull::new(unsafe { std::alloc::alloc_zeroed(layout) }) else {
+            std::alloc::handle_alloc_error(layout);
+        };
+        Ok(Self { ptr, layout, len })
+    }
+
+    pub fn slice(&mut self) -> ScratchSlice<'_> {
+        let len = self.len;
+        ScratchSlice {
+            buf: self.full_mut(),
+            len,
+        }
+    }
+
+    fn full_mut(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.layout.size()) }
+    }
+}
+
+impl Drop for ScratchBuffer {
+    fn drop(&mut self) {
+        unsafe { std::alloc::dealloc(self.ptr.as_ptr(), self.layout) };
+    }
+}
+
+/// A mutable view of the input's copy in a [`ScratchBuffer`]. Derefs to just the input bytes, the
+/// zeroed padding after them is available through [`ScratchSlice::padded`].
+pub struct ScratchSlice<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl ScratchSlice<'_> {
+    /// The input along with the zeroed padding after it.
+    pub fn padded(&self) -> &[u8] {
+        self.buf
+    }
+
+    pub fn padded_mut(&mut self) -> &mut [u8] {
+        self.buf
+    }
+
+    /// Copies `input` into the start of the buffer, and zeroes everything after it, undoing
+    /// anything a previous sample wrote.
+    pub fn restore(&mut self, input: &[u8]) {
+        assert_eq!(input.len(), self.len);
+        self.buf[..input.len()].copy_from_slice(input);
+        self.buf[input.len()..].fill(0);
+    }
+}
+
+impl Deref for ScratchSlice<'_> {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        &self.buf[..self.len]
+    }
+}
+
+impl DerefMut for ScratchSlice<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buf[..self.len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padding_and_alignment() {
+        for len in [0, 1, 63, 64, 65, 1000] {
+            let input = vec![b'x'; len];
+            let mut scratch = ScratchBuffer::new(len, 64, 32).unwrap();
+            let mut slice = scratch.slice();
+            slice.restore(&input);
+
+            assert_eq!(slice.as_ptr() as usize % 64, 0);
+            assert_eq!(&*slice, &input[..]);
+            assert!(slice.padded().len() >= len + MIN_PADDING);
+            assert!(slice.padded()[len..].iter().all(|&b| b == 0));
+
+            slice.padded_mut().fill(b'y');
+            slice.restore(&input);
+            assert_eq!(&*slice, &input[..]);
+            assert!(slice.padded()[len..].iter().all(|&b| b == 0));
+        }
+    }
+
+    #[test]
+    fn test_bad_alignment() {
+        assert!(ScratchBuffer::new(10, 3, 32).is_err());
+        assert!(ScratchBuffer::new(10, 0, 32).is_err());
+    }
+}
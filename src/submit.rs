@@ -0,0 +1,262 @@
+use std::{
+    io::Write,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::fetch::Fetcher;
+
+/// What the site made of a submitted answer.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Outcome {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// The answer wasn't judged, because we submitted too recently.
+    RateLimited {
+        wait: Option<String>,
+    },
+    /// The part was already solved, or isn't unlocked yet.
+    WrongLevel,
+    /// The response didn't look like anything we know about.
+    Unrecognized,
+}
+
+impl Outcome {
+    /// Whether the site actually judged the answer to be wrong.
+    pub fn is_wrong(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Incorrect | Outcome::TooHigh | Outcome::TooLow
+        )
+    }
+}
+
+impl std::fmt::Display for Outcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "\x1b[32mcorrect\x1b[0m"),
+            Outcome::Incorrect => write!(f, "\x1b[31mincorrect\x1b[0m"),
+            Outcome::TooHigh => write!(f, "\x1b[31mincorrect\x1b[0m (too high)"),
+            Outcome::TooLow => write!(f, "\x1b[31mincorrect\x1b[0m (too low)"),
+            Outcome::RateLimited { wait: Some(wait) } => {
+                write!(f, "\x1b[33mrate limited\x1b[0m ({wait} left to wait)")
+            }
+            Outcome::RateLimited { wait: None } => write!(f, "\x1b[33mrate limited\x1b[0m"),
+            Outcome::WrongLevel => write!(f, "\x1b[33malready solved or not unlocked\x1b[0m"),
+            Outcome::Unrecognized => write!(f, "\x1b[33munrecognized response\x1b[0m"),
+        }
+    }
+}
+
+pub fn parse_response(html: &str) -> Outcome {
+    if html.contains("That's the right answer") {
+        Outcome::Correct
+    } else if html.contains("That's not the right answer") {
+        if html.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if html.contains("your answer is too low") {
+            Outcome::TooLow
+        } else {
+            Outcome::Incorrect
+        }
+    } else if html.contains("You gave an answer too recently") {
+        let wait = html
+            .split_once("You have ")
+            .and_then(|(_, rest)| rest.split_once(" left to wait"))
+            .map(|(wait, _)| wait.to_string());
+        Outcome::RateLimited { wait }
+    } else if html.contains("You don't seem to be solving the right level") {
+        Outcome::WrongLevel
+    } else {
+        Outcome::Unrecognized
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Attempt {
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    pub part: u32,
+    pub answer: String,
+    pub outcome: Outcome,
+}
+
+/// Every answer we've submitted for a day, stored as one line of JSON per attempt.
+pub struct SubmissionLog {
+    path: PathBuf,
+    pub attempts: Vec<Attempt>,
+}
+
+impl SubmissionLog {
+    pub fn load(path: PathBuf) -> eyre::Result<Self> {
+        let mut attempts = vec![];
+        if std::fs::exists(&path)? {
+            for line in std::fs::read_to_string(&path)?.lines() {
+                if !line.trim().is_empty() {
+                    attempts.push(serde_json::from_str(line)?);
+                }
+            }
+        }
+        Ok(Self { path, attempts })
+    }
+
+    pub fn push(&mut self, attempt: Attempt) -> eyre::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&attempt)?)?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    /// Refuses answers that the log already tells us are wrong, without having to ask the site.
+    pub fn check(&self, part: u32, answer: &str) -> eyre::Result<()> {
+        let attempts = self.attempts.iter().filter(|attempt| attempt.part == part);
+        let number = answer.parse::<i128>().ok();
+        for attempt in attempts {
+            if attempt.outcome == Outcome::Correct {
+                eyre::bail!(
+                    "part {part} was already solved with {}, not submitting {answer}",
+                    attempt.answer
+                );
+            }
+            if attempt.outcome.is_wrong() && attempt.answer == answer {
+                eyre::bail!("{answer} was already submitted for part {part}, and was wrong");
+            }
+            let (Some(number), Ok(previous)) = (number, attempt.answer.parse::<i128>()) else {
+                continue;
+            };
+            if attempt.outcome == Outcome::TooHigh && number >= previous {
+                eyre::bail!("{answer} can't be right, {previous} was already too high");
+            }
+            if attempt.outcome == Outcome::TooLow && number <= previous {
+                eyre::bail!("{answer} can't be right, {previous} was already too low");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Submits an answer if the log doesn't already rule it out, and logs whatever the site said.
+pub fn submit_answer(
+    fetcher: &Fetcher,
+    log: &mut SubmissionLog,
+    year: u32,
+    day: u32,
+    part: u32,
+    answer: &str,
+) -> eyre::Result<Outcome> {
+    log.check(part, answer)?;
+
+    let level = part.to_string();
+    let response = fetcher.post_form(
+        &format!("/{year}/day/{day}/answer"),
+        &[("level", &level), ("answer", answer)],
+    )?;
+    let outcome = parse_response(&String::from_utf8_lossy(&response));
+
+    log.push(Attempt {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        part,
+        answer: answer.into(),
+        outcome: outcome.clone(),
+    })?;
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fetch::tests::{serve, temp_dir};
+    use std::time::Duration;
+
+    #[test]
+    fn test_parse_response() {
+        let page = |text: &str| format!("<main><article><p>{text}</p></article></main>");
+        assert_eq!(
+            parse_response(&page(
+                "That's the right answer! You are one gold star closer."
+            )),
+            Outcome::Correct
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too high."
+            )),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            parse_response(&page(
+                "That's not the right answer; your answer is too low."
+            )),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            parse_response(&page("That's not the right answer. If you're stuck...")),
+            Outcome::Incorrect
+        );
+        assert_eq!(
+            parse_response(&page(
+                "You gave an answer too recently. You have 4m 39s left to wait."
+            )),
+            Outcome::RateLimited {
+                wait: Some("4m 39s".into())
+            }
+        );
+        assert_eq!(
+            parse_response(&page("You don't seem to be solving the right level.")),
+            Outcome::WrongLevel
+        );
+    }
+
+    #[test]
+    fn test_submission_guards() {
+        let dir = temp_dir("submit");
+        let (base_url, requests) = serve(vec![
+            (
+                200,
+                "That's not the right answer; your answer is too high.".into(),
+            ),
+            (
+                200,
+                "You gave an answer too recently. You have 30s left to wait.".into(),
+            ),
+            (200, "That's the right answer!".into()),
+        ]);
+        let fetcher =
+            Fetcher::new(&base_url, "session=abc", Duration::ZERO, dir.join(".last")).unwrap();
+        let mut log = SubmissionLog::load(dir.join("day1.submissions")).unwrap();
+
+        let outcome = submit_answer(&fetcher, &mut log, 2024, 1, 1, "500").unwrap();
+        assert_eq!(outcome, Outcome::TooHigh);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "POST /2024/day/1/answer HTTP/1.1");
+        assert_eq!(request.body, "level=1&answer=500");
+
+        // neither of these should reach the server.
+        assert!(submit_answer(&fetcher, &mut log, 2024, 1, 1, "500").is_err());
+        assert!(submit_answer(&fetcher, &mut log, 2024, 1, 1, "501").is_err());
+
+        let outcome = submit_answer(&fetcher, &mut log, 2024, 1, 1, "400").unwrap();
+        assert!(matches!(outcome, Outcome::RateLimited { .. }));
+        // being rate limited doesn't say anything about the answer, so it can be tried again.
+        let outcome = submit_answer(&fetcher, &mut log, 2024, 1, 1, "400").unwrap();
+        assert_eq!(outcome, Outcome::Correct);
+        assert!(submit_answer(&fetcher, &mut log, 2024, 1, 1, "300").is_err());
+
+        let log = SubmissionLog::load(dir.join("day1.submissions")).unwrap();
+        assert_eq!(log.attempts.len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
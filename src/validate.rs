@@ -0,0 +1,331 @@
+//! Checks that an input has the shape a day's solutions assume, before running them.
+//!
+//! Lots of solutions here read fixed offsets or assume a grid of a particular size, and will
+//! happily panic or produce garbage on an input that breaks those assumptions. Validators point at
+//! the exact spot that breaks one instead.
+
+use std::ops::Range;
+
+/// Where and how an input broke an assumption. Lines and columns are 1-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InputError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+    source: String,
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        writeln!(f, "  | {}", self.source)?;
+        write!(f, "  | {:>width$}", "^", width = self.column)
+    }
+}
+
+impl std::error::Error for InputError {}
+
+pub type ValidateResult<T = ()> = Result<T, InputError>;
+
+/// A number pulled out of a line by a template.
+#[derive(Copy, Clone, Debug)]
+pub struct Field<'a> {
+    pub value: i64,
+    line: usize,
+    column: usize,
+    source: &'a str,
+}
+
+impl Field<'_> {
+    pub fn in_range(&self, range: Range<i64>, what: &str) -> ValidateResult {
+        if range.contains(&self.value) {
+            return Ok(());
+        }
+        Err(InputError {
+            line: self.line,
+            column: self.column,
+            message: format!(
+                "{what} {} is outside of {}..{}",
+                self.value, range.start, range.end
+            ),
+            source: self.source.into(),
+        })
+    }
+}
+
+/// Walks through an input line by line, checking each one against what's expected of it.
+pub struct Validator<'a> {
+    lines: std::iter::Peekable<std::str::SplitTerminator<'a, char>>,
+    /// The number of lines consumed so far, which is also the 1-based number of the last one.
+    line: usize,
+    last: &'a str,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            // not `lines`, which would quietly accept `\r\n` line endings that throw off every
+            // solution that indexes rows by a fixed stride.
+            lines: input.split_terminator('\n').peekable(),
+            line: 0,
+            last: "",
+        }
+    }
+
+    fn error_at(&self, column: usize, message: impl Into<String>) -> InputError {
+        InputError {
+            line: self.line,
+            column,
+            message: message.into(),
+            source: self.last.into(),
+        }
+    }
+
+    /// Moves on to `line`, rejecting it if it has a `\r` in it.
+    fn advance(&mut self, line: &'a str) -> ValidateResult<&'a str> {
+        self.line += 1;
+        match line.find('\r') {
+            Some(column) => {
+                // cut the line short, so the `\r` doesn't mangle the error message.
+                self.last = &line[..column];
+                Err(self.error_at(
+                    column + 1,
+                    "unexpected '\\r', lines must end in a bare '\\n'",
+                ))
+            }
+            None => {
+                self.last = line;
+                Ok(line)
+            }
+        }
+    }
+
+    fn next_line(&mut self, expected: &str) -> ValidateResult<&'a str> {
+        match self.lines.next() {
+            Some(line) => self.advance(line),
+            None => {
+                self.line += 1;
+                self.last = "";
+                Err(self.error_at(1, format!("unexpected end of input, expected {expected}")))
+            }
+        }
+    }
+
+    pub fn has_more(&mut self) -> bool {
+        self.lines.peek().is_some()
+    }
+
+    /// Checks the next line against `check`, which reports a 0-based column and a message when the
+    /// line is wrong.
+    pub fn line_with(
+        &mut self,
+        expected: &str,
+        check: impl FnOnce(&str) -> Result<(), (usize, String)>,
+    ) -> ValidateResult {
+        let line = self.next_line(expected)?;
+        check(line).map_err(|(column, message)| self.error_at(column + 1, message))
+    }
+
+    /// Matches the next line against `template`, returning the numbers it contained.
+    ///
+    /// Templates are literal text, except for `{d}` (a single digit), `{n}` (an unsigned number)
+    /// and `{i}` (a number with an optional `-` sign).
+    pub fn line(&mut self, template: &str) -> ValidateResult<Vec<Field<'a>>> {
+        let line = self.next_line(&format!("a line like `{template}`"))?;
+        match_template(line, template)
+            .map(|fields| {
+                fields
+                    .into_iter()
+                    .map(|(column, value)| Field {
+                        value,
+                        line: self.line,
+                        column: column + 1,
+                        source: line,
+                    })
+                    .collect()
+            })
+            .map_err(|(column, message)| self.error_at(column + 1, message))
+    }
+
+    pub fn blank(&mut self) -> ValidateResult {
+        self.line_with("a blank line", |line| match line.is_empty() {
+            true => Ok(()),
+            false => Err((0, "expected a blank line".into())),
+        })
+    }
+
+    /// Checks a `width` by `height` grid of characters that `allowed` accepts.
+    pub fn grid(
+        &mut self,
+        width: usize,
+        height: usize,
+        allowed: impl Fn(u8) -> bool,
+    ) -> ValidateResult {
+        for row in 0..height {
+            let line = self.next_line(&format!("grid row {} of {height}", row + 1))?;
+            if let Some(column) = line.bytes().position(|ch| !allowed(ch)) {
+                let ch = line[column..].chars().next().unwrap();
+                return Err(self.error_at(column + 1, format!("unexpected character {ch:?}")));
+            }
+            if line.len() != width {
+                return Err(self.error_at(
+                    line.len().min(width) + 1,
+                    format!("expected a row of {width} characters, found {}", line.len()),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Matches every remaining line against `template`, handing each line's numbers to `check`.
+    pub fn each_line(
+        &mut self,
+        template: &str,
+        mut check: impl FnMut(&[Field<'a>]) -> ValidateResult,
+    ) -> ValidateResult {
+        while self.has_more() {
+            check(&self.line(template)?)?;
+        }
+        Ok(())
+    }
+
+    /// Checks that every remaining line only contains characters that `allowed` accepts.
+    pub fn each_line_chars(&mut self, allowed: impl Fn(u8) -> bool) -> ValidateResult {
+        while self.has_more() {
+            let line = self.next_line("more lines")?;
+            if let Some(column) = line.bytes().position(|ch| !allowed(ch)) {
+                let ch = line[column..].chars().next().unwrap();
+                return Err(self.error_at(column + 1, format!("unexpected character {ch:?}")));
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes sure nothing but blank lines is left over.
+    pub fn end(&mut self) -> ValidateResult {
+        while let Some(line) = self.lines.next() {
+            if !self.advance(line)?.is_empty() {
+                return Err(self.error_at(1, "expected the end of the input"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns each number in `line` along with the 0-based column it started at, or the column where
+/// `line` stopped matching `template`.
+fn match_template(line: &str, template: &str) -> Result<Vec<(usize, i64)>, (usize, String)> {
+    let line = line.as_bytes();
+    let mut fields = vec![];
+    let mut cur = 0;
+    let mut rest = template;
+
+    fn parse_digits(line: &[u8], cur: &mut usize) -> Option<i64> {
+        let begin = *cur;
+        while *cur < line.len() && line[*cur].is_ascii_digit() {
+            *cur += 1;
+        }
+        std::str::from_utf8(&line[begin..*cur]).ok()?.parse().ok()
+    }
+
+    while !rest.is_empty() {
+        let begin = cur;
+        let found = line.get(cur).map(|&ch| ch as char);
+        let expected = |what: &str| match found {
+            Some(ch) => (begin, format!("expected {what}, found {ch:?}")),
+            None => (begin, format!("expected {what}, found the end of the line")),
+        };
+
+        if let Some(after) = rest.strip_prefix("{d}") {
+            if !found.is_some_and(|ch| ch.is_ascii_digit()) {
+                return Err(expected("a digit"));
+            }
+            cur += 1;
+            fields.push((begin, (line[begin] - b'0') as i64));
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{n}") {
+            let value = parse_digits(line, &mut cur).ok_or_else(|| expected("a number"))?;
+            fields.push((begin, value));
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("{i}") {
+            let negative = found == Some('-');
+            cur += negative as usize;
+            let value = parse_digits(line, &mut cur).ok_or_else(|| expected("a number"))?;
+            fields.push((begin, if negative { -value } else { value }));
+            rest = after;
+        } else {
+            let ch = rest.chars().next().unwrap();
+            if found != Some(ch) {
+                return Err(expected(&format!("{ch:?}")));
+            }
+            cur += ch.len_utf8();
+            rest = &rest[ch.len_utf8()..];
+        }
+    }
+
+    if cur != line.len() {
+        return Err((cur, "expected the end of the line".into()));
+    }
+
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template() {
+        assert_eq!(
+            match_template("p=0,-4 v=3,-3", "p={i},{i} v={i},{i}"),
+            Ok(vec![(2, 0), (4, -4), (9, 3), (11, -3)])
+        );
+        assert_eq!(
+            match_template("Button A: X+9, Y+34", "Button A: X+{d}{d}, Y+{d}{d}")
+                .unwrap_err()
+                .0,
+            13
+        );
+        assert!(match_template("1,2,", "{n},{n}").is_err());
+    }
+
+    #[test]
+    fn test_grid_reports_position() {
+        let mut validator = Validator::new("..#\n.x.\n");
+        let err = validator.grid(3, 2, |ch| b".#".contains(&ch)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+
+        let mut validator = Validator::new("..#\n..\n");
+        let err = validator.grid(3, 2, |ch| b".#".contains(&ch)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let mut validator = Validator::new("..#\n");
+        let err = validator.grid(3, 2, |ch| b".#".contains(&ch)).unwrap_err();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn test_rejects_crlf() {
+        let mut validator = Validator::new("..#\r\n.#.\r\n");
+        let err = validator.grid(3, 2, |ch| b".#".contains(&ch)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 4));
+
+        let mut validator = Validator::new("3,71\n\r\n");
+        validator.line("{n},{n}").unwrap();
+        let err = validator.end().unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn test_field_range() {
+        let mut validator = Validator::new("3,71\n");
+        let fields = validator.line("{n},{n}").unwrap();
+        assert!(fields[0].in_range(0..71, "x").is_ok());
+        let err = fields[1].in_range(0..71, "y").unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+    }
+}
@@ -0,0 +1,320 @@
+#[cfg(feature = "visualize")]
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+pub type Rgb = [u8; 3];
+
+/// Drawn for cells whose palette index is out of range, so they stand out.
+const MISSING_COLOR: Rgb = [255, 0, 255];
+
+/// A snapshot of a grid, where every cell is an index into a palette.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    palette: &'static [Rgb],
+    cells: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize, palette: &'static [Rgb]) -> Self {
+        Self {
+            width,
+            height,
+            palette,
+            cells: vec![0; width * height],
+        }
+    }
+
+    pub fn from_fn<F>(width: usize, height: usize, palette: &'static [Rgb], mut cell: F) -> Self
+    where
+        F: FnMut(usize, usize) -> u8,
+    {
+        let mut frame = Self::new(width, height, palette);
+        for y in 0..height {
+            for x in 0..width {
+                frame.cells[y * width + x] = cell(x, y);
+            }
+        }
+        frame
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, cell: u8) {
+        self.cells[y * self.width + x] = cell;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.cells[y * self.width + x]
+    }
+
+    pub fn color(&self, x: usize, y: usize) -> Rgb {
+        let cell = self.get(x, y) as usize;
+        self.palette.get(cell).copied().unwrap_or(MISSING_COLOR)
+    }
+}
+
+/// What variants feed frames to. It does nothing unless the runner was built with the `visualize`
+/// feature and given `--visualize`, and the closure passed to [`Visualizer::frame`] only runs when
+/// the frame is actually going to be written.
+#[derive(Default)]
+pub struct Visualizer {
+    #[cfg(feature = "visualize")]
+    writer: Option<FrameWriter>,
+}
+
+impl Visualizer {
+    #[cfg(feature = "visualize")]
+    pub fn new(writer: FrameWriter) -> Self {
+        Self {
+            writer: Some(writer),
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_enabled(&self) -> bool {
+        #[cfg(feature = "visualize")]
+        return self.writer.is_some();
+        #[cfg(not(feature = "visualize"))]
+        return false;
+    }
+
+    #[inline(always)]
+    pub fn frame<F>(&mut self, build: F)
+    where
+        F: FnOnce() -> Frame,
+    {
+        #[cfg(feature = "visualize")]
+        if let Some(writer) = &mut self.writer {
+            if writer.wants_frame() {
+                writer.push(&build());
+            }
+        }
+        #[cfg(not(feature = "visualize"))]
+        let _ = build;
+    }
+
+    #[cfg(feature = "visualize")]
+    pub fn take(&mut self) -> Option<FrameWriter> {
+        self.writer.take()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameFormat {
+    /// One PNG per frame.
+    Png,
+    /// One binary PPM per frame, for piping into other tools without needing a decoder.
+    Ppm,
+    /// A single animated GIF of every frame.
+    Gif,
+}
+
+impl std::str::FromStr for FrameFormat {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(Self::Png),
+            "ppm" => Ok(Self::Ppm),
+            "gif" => Ok(Self::Gif),
+            _ => eyre::bail!("unknown frame format '{s}', expected 'png', 'ppm' or 'gif'"),
+        }
+    }
+}
+
+#[cfg(feature = "visualize")]
+type GifEncoder = gif::Encoder<BufWriter<File>>;
+
+/// Writes frames into a directory, scaling every cell up to a `scale` by `scale` square.
+#[cfg(feature = "visualize")]
+pub struct FrameWriter {
+    dir: PathBuf,
+    format: FrameFormat,
+    scale: usize,
+    stride: usize,
+    seen: usize,
+    written: usize,
+    /// The animation, and the size every one of its frames has to be.
+    gif: Option<(GifEncoder, (u16, u16))>,
+    // errors are held on to until the variant is done, rather than stopping it partway through.
+    error: Option<eyre::Report>,
+}
+
+#[cfg(feature = "visualize")]
+impl FrameWriter {
+    pub fn new(dir: &Path, format: FrameFormat, scale: usize, stride: usize) -> eyre::Result<Self> {
+        if scale == 0 || stride == 0 {
+            eyre::bail!("the frame scale and stride must be at least 1");
+        }
+        std::fs::create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.into(),
+            format,
+            scale,
+            stride,
+            seen: 0,
+            written: 0,
+            gif: None,
+            error: None,
+        })
+    }
+
+    /// Whether the next frame should be built, given that only every `stride`th one is kept.
+    fn wants_frame(&mut self) -> bool {
+        let wants = self.error.is_none() && self.seen.is_multiple_of(self.stride);
+        self.seen += 1;
+        wants
+    }
+
+    fn push(&mut self, frame: &Frame) {
+        match self.write(frame) {
+            Ok(()) => self.written += 1,
+            Err(err) => self.error = Some(err),
+        }
+    }
+
+    fn write(&mut self, frame: &Frame) -> eyre::Result<()> {
+        let (width, height) = (frame.width * self.scale, frame.height * self.scale);
+        let name = format!("frame{:05}", self.written);
+        match self.format {
+            FrameFormat::Png => {
+                let file = BufWriter::new(File::create(self.dir.join(name + ".png"))?);
+                let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+                encoder.set_color(png::ColorType::Rgb);
+                encoder.set_depth(png::BitDepth::Eight);
+                let mut writer = encoder.write_header()?;
+                writer.write_image_data(&self.rgb_pixels(frame))?;
+            }
+            FrameFormat::Ppm => {
+                let mut file = BufWriter::new(File::create(self.dir.join(name + ".ppm"))?);
+                write!(file, "P6\n{width} {height}\n255\n")?;
+                file.write_all(&self.rgb_pixels(frame))?;
+                file.flush()?;
+            }
+            FrameFormat::Gif => self.write_gif(frame, width, height)?,
+        }
+        Ok(())
+    }
+
+    fn write_gif(&mut self, frame: &Frame, width: usize, height: usize) -> eyre::Result<()> {
+        let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+            eyre::bail!("{width}x{height} is too big for a gif frame");
+        };
+        if frame.palette.len() > 255 {
+            eyre::bail!("gif frames can't have more than 255 colors");
+        }
+
+        let (encoder, size) = match &mut self.gif {
+            Some(gif) => gif,
+            None => {
+                let file = BufWriter::new(File::create(self.dir.join("animation.gif"))?);
+                let mut encoder = gif::Encoder::new(file, width, height, &[])?;
+                encoder.set_repeat(gif::Repeat::Infinite)?;
+                self.gif.insert((encoder, (width, height)))
+            }
+        };
+        if *size != (width, height) {
+            eyre::bail!("every frame of a gif has to be the same size");
+        }
+
+        // out of range cells get an extra palette entry, rather than having to be checked for.
+        let mut palette = frame.palette.concat();
+        palette.extend(MISSING_COLOR);
+        let missing = frame.palette.len() as u8;
+        let indices = scaled(frame, self.scale, |cell| [cell.min(missing)]);
+
+        let mut gif_frame = gif::Frame::from_palette_pixels(width, height, indices, palette, None);
+        gif_frame.delay = 5;
+        encoder.write_frame(&gif_frame)?;
+        Ok(())
+    }
+
+    fn rgb_pixels(&self, frame: &Frame) -> Vec<u8> {
+        scaled(frame, self.scale, |cell| {
+            frame
+                .palette
+                .get(cell as usize)
+                .copied()
+                .unwrap_or(MISSING_COLOR)
+        })
+    }
+
+    /// Flushes everything that's been written, returning where it went and how many frames there
+    /// were.
+    pub fn finish(self) -> eyre::Result<(PathBuf, usize)> {
+        if let Some(err) = self.error {
+            return Err(err.wrap_err(format!("failed to write frames to {}", self.dir.display())));
+        }
+        if let Some((encoder, _)) = self.gif {
+            encoder.into_inner()?.flush()?;
+        }
+        Ok((self.dir, self.written))
+    }
+}
+
+/// Turns every cell into a `scale` by `scale` square of `pixel(cell)`.
+#[cfg(feature = "visualize")]
+fn scaled<const N: usize>(frame: &Frame, scale: usize, pixel: impl Fn(u8) -> [u8; N]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(frame.cells.len() * scale * scale * N);
+    for row in frame.cells.chunks(frame.width.max(1)) {
+        let start = out.len();
+        for &cell in row {
+            for _ in 0..scale {
+                out.extend(pixel(cell));
+            }
+        }
+        let line = out.len() - start;
+        for _ in 1..scale {
+            out.extend_from_within(start..start + line);
+        }
+    }
+    out
+}
+
+#[cfg(all(test, feature = "visualize"))]
+mod tests {
+    use super::*;
+    use crate::fetch::tests::temp_dir;
+
+    const PALETTE: &[Rgb] = &[[0, 0, 0], [255, 255, 255]];
+
+    #[test]
+    fn test_write_frames() {
+        let dir = temp_dir("visualize");
+        for format in [FrameFormat::Png, FrameFormat::Ppm, FrameFormat::Gif] {
+            let mut writer = FrameWriter::new(&dir, format, 2, 2).unwrap();
+            let mut visualizer = Visualizer::new(writer);
+            for i in 0..3 {
+                visualizer.frame(|| Frame::from_fn(3, 2, PALETTE, |x, y| (x + y + i) as u8 % 3));
+            }
+            writer = visualizer.take().unwrap();
+            let (_, written) = writer.finish().unwrap();
+            assert_eq!(written, 2);
+        }
+
+        let ppm = std::fs::read(dir.join("frame00001.ppm")).unwrap();
+        let header = b"P6\n6 4\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(ppm.len(), header.len() + 6 * 4 * 3);
+        // the third frame's top left cell is 2, which isn't in the palette.
+        assert_eq!(
+            &ppm[header.len()..header.len() + 6],
+            &[255, 0, 255, 255, 0, 255]
+        );
+
+        assert!(std::fs::exists(dir.join("frame00001.png")).unwrap());
+        assert!(std::fs::exists(dir.join("animation.gif")).unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
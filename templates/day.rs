@@ -0,0 +1,33 @@
+use crate::prelude::*;
+
+#[variant]
+fn part1(ctx: &mut RunContext) -> eyre::Result<u64> {
+    let _input = ctx.input;
+    eyre::bail!("day {day} part 1 is not solved yet")
+}
+
+#[variant]
+fn part2(ctx: &mut RunContext) -> eyre::Result<u64> {
+    let _input = ctx.input;
+    eyre::bail!("day {day} part 2 is not solved yet")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // paste the example from the puzzle page here.
+    const EXAMPLE: &str = "";
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_day{day}_part1() {
+        assert_eq!(run_on(EXAMPLE, part1).unwrap(), 0);
+    }
+
+    #[test]
+    #[ignore = "not solved yet"]
+    fn test_day{day}_part2() {
+        assert_eq!(run_on(EXAMPLE, part2).unwrap(), 0);
+    }
+}